@@ -0,0 +1,316 @@
+//! On-demand ("socket-activated") services.
+//!
+//! For a service with an `on_demand` config, ServiceMaster itself listens on the
+//! service's `port`, while the real process is configured to listen on
+//! `backend_port`. The first incoming connection starts the service, waits until
+//! the backend port accepts connections and then proxies traffic through. Once no
+//! connection has been active for the idle timeout, the service is stopped again.
+//!
+//! The listener binds both loopback addresses, since `localhost` may resolve to
+//! `::1`. It keeps the port while the service runs: every connection has to pass
+//! through it to be counted for the idle timeout, and a released port could be
+//! taken by another process before the service is stopped and needs it back.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::health::{run_port_check, PortCheckSpec};
+use crate::{find_service, get_loaded_services, load_all_metadata, load_service, unix_now, unload_service};
+
+const REAPER_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Per-service on-demand config stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct OnDemandConfig {
+    /// Port the real service listens on; ServiceMaster proxies `port` to it
+    pub backend_port: u16,
+    /// Stop the service after this long without active connections
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+    /// How long a connection may wait for the backend to come up
+    #[serde(default = "default_ready_timeout_secs")]
+    pub ready_timeout_secs: u64,
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    600
+}
+
+fn default_ready_timeout_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct OnDemandStatus {
+    pub label: String,
+    pub port: u16,
+    pub backend_port: u16,
+    pub listening: bool,
+    pub error: Option<String>,
+    pub active_connections: usize,
+    /// Unix timestamp (seconds) of the last connection open/close
+    pub last_activity: u64,
+    pub idle_timeout_secs: u64,
+}
+
+#[derive(Default)]
+struct Stats {
+    active: AtomicUsize,
+    last_activity: AtomicU64,
+    /// The running instance was started by a proxied connection. Only those are
+    /// stopped when idle; a service started by hand is left alone.
+    started_here: AtomicBool,
+    /// Serializes start attempts from concurrent first connections
+    starting: Mutex<()>,
+}
+
+struct Listener {
+    port: u16,
+    config: OnDemandConfig,
+    stop: Arc<AtomicBool>,
+    stats: Arc<Stats>,
+    error: Option<String>,
+    /// Bound address and the accept thread owning its socket; joined before the port is bound again
+    threads: Vec<(SocketAddr, JoinHandle<()>)>,
+}
+
+/// Managed state holding one listener per on-demand service
+#[derive(Default)]
+pub struct OnDemandManager {
+    listeners: Mutex<HashMap<String, Listener>>,
+}
+
+/// IPv4 and IPv6 loopback, in the order connections to the backend try them
+fn loopback_addrs(port: u16) -> [SocketAddr; 2] {
+    [
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, port)),
+    ]
+}
+
+/// Bind every loopback address; a machine without IPv6 only gets the IPv4 socket
+fn bind_loopback(port: u16) -> Result<Vec<TcpListener>, String> {
+    let mut sockets = vec![];
+    for addr in loopback_addrs(port) {
+        match TcpListener::bind(addr) {
+            Ok(socket) => sockets.push(socket),
+            Err(e) if addr.is_ipv6() && e.kind() == io::ErrorKind::AddrNotAvailable => {}
+            Err(e) => return Err(format!("Failed to listen on {}: {}", addr, e)),
+        }
+    }
+    Ok(sockets)
+}
+
+/// Bind/unbind listeners so they match the current metadata
+pub(crate) fn sync_listeners(app: &AppHandle) {
+    let wanted: HashMap<String, (u16, OnDemandConfig)> = load_all_metadata()
+        .into_iter()
+        .filter_map(|(label, meta)| Some((label, (meta.port?, meta.on_demand?))))
+        .collect();
+
+    let manager = app.state::<OnDemandManager>();
+    let Ok(mut listeners) = manager.listeners.lock() else { return };
+
+    listeners.retain(|label, listener| {
+        let keep = wanted
+            .get(label)
+            .is_some_and(|(port, config)| *port == listener.port && *config == listener.config);
+        if !keep {
+            stop_listener(listener);
+        }
+        keep
+    });
+
+    for (label, (port, config)) in wanted {
+        if listeners.contains_key(&label) {
+            continue;
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let stats = Arc::new(Stats::default());
+        stats.last_activity.store(unix_now(), Ordering::SeqCst);
+
+        let (error, threads) = match bind_loopback(port) {
+            Ok(sockets) => {
+                let threads = sockets
+                    .into_iter()
+                    .filter_map(|socket| {
+                        let addr = socket.local_addr().ok()?;
+                        let (label, config, stop, stats) = (label.clone(), config.clone(), stop.clone(), stats.clone());
+                        Some((addr, std::thread::spawn(move || accept_loop(socket, label, config, stop, stats))))
+                    })
+                    .collect();
+                (None, threads)
+            }
+            Err(e) => (Some(e), vec![]),
+        };
+        listeners.insert(
+            label,
+            Listener {
+                port,
+                config,
+                stop,
+                stats,
+                error,
+                threads,
+            },
+        );
+    }
+}
+
+/// Stop the accept threads and wait until they have closed their sockets, so the
+/// port can be bound again right away
+fn stop_listener(listener: &mut Listener) {
+    listener.stop.store(true, Ordering::SeqCst);
+    for (addr, thread) in listener.threads.drain(..) {
+        // Wake the blocking accept() so the thread sees the stop flag
+        let _ = TcpStream::connect_timeout(&addr, Duration::from_millis(200));
+        let _ = thread.join();
+    }
+}
+
+fn accept_loop(socket: TcpListener, label: String, config: OnDemandConfig, stop: Arc<AtomicBool>, stats: Arc<Stats>) {
+    for stream in socket.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(client) = stream else { continue };
+        let (label, config, stats) = (label.clone(), config.clone(), stats.clone());
+        std::thread::spawn(move || {
+            stats.active.fetch_add(1, Ordering::SeqCst);
+            stats.last_activity.store(unix_now(), Ordering::SeqCst);
+            if let Err(e) = handle_connection(client, &label, &config, &stats) {
+                eprintln!("On-demand proxy for {} failed: {}", label, e);
+            }
+            stats.active.fetch_sub(1, Ordering::SeqCst);
+            stats.last_activity.store(unix_now(), Ordering::SeqCst);
+        });
+    }
+}
+
+/// Start the service if its backend is not listening yet, then wait for the backend port
+fn ensure_backend(label: &str, config: &OnDemandConfig, stats: &Stats) -> Result<(), String> {
+    if run_port_check(config.backend_port, &PortCheckSpec::default()).is_up() {
+        return Ok(());
+    }
+
+    let _guard = stats.starting.lock().map_err(|e| e.to_string())?;
+    let service = find_service(label)?;
+    if !service.is_loaded {
+        load_service(service.file_path)?;
+        stats.started_here.store(true, Ordering::SeqCst);
+        stats.last_activity.store(unix_now(), Ordering::SeqCst);
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(config.ready_timeout_secs);
+    while Instant::now() < deadline {
+        if run_port_check(config.backend_port, &PortCheckSpec::default()).is_up() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    Err(format!(
+        "Backend port {} did not open within {}s",
+        config.backend_port, config.ready_timeout_secs
+    ))
+}
+
+fn handle_connection(client: TcpStream, label: &str, config: &OnDemandConfig, stats: &Stats) -> Result<(), String> {
+    ensure_backend(label, config, stats)?;
+
+    let backend = TcpStream::connect(&loopback_addrs(config.backend_port)[..]).map_err(|e| e.to_string())?;
+    let (mut client_read, mut backend_write) = (
+        client.try_clone().map_err(|e| e.to_string())?,
+        backend.try_clone().map_err(|e| e.to_string())?,
+    );
+    let upstream = std::thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut backend_write);
+        let _ = backend_write.shutdown(Shutdown::Write);
+    });
+
+    let (mut backend_read, mut client_write) = (backend, client);
+    let _ = io::copy(&mut backend_read, &mut client_write);
+    let _ = client_write.shutdown(Shutdown::Write);
+    let _ = upstream.join();
+    Ok(())
+}
+
+/// Periodically stop on-demand services that were started by a connection and have
+/// had no connections for their idle timeout
+pub(crate) fn spawn_reaper(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(REAPER_INTERVAL);
+
+        // (label, stats, idle timeout) of services a connection started
+        let started: Vec<(String, Arc<Stats>, u64)> = {
+            let manager = app.state::<OnDemandManager>();
+            let Ok(listeners) = manager.listeners.lock() else { continue };
+            listeners
+                .iter()
+                .filter(|(_, l)| l.error.is_none() && l.stats.started_here.load(Ordering::SeqCst))
+                .map(|(label, l)| (label.clone(), l.stats.clone(), l.config.idle_timeout_secs))
+                .collect()
+        };
+        if started.is_empty() {
+            continue;
+        }
+
+        let loaded = get_loaded_services();
+        let now = unix_now();
+        for (label, stats, idle_timeout_secs) in started {
+            if !loaded.contains_key(&label) {
+                // Stopped by hand since; a later manual start is not ours to reap
+                stats.started_here.store(false, Ordering::SeqCst);
+                continue;
+            }
+            let idle = stats.active.load(Ordering::SeqCst) == 0
+                && now.saturating_sub(stats.last_activity.load(Ordering::SeqCst)) >= idle_timeout_secs;
+            if !idle {
+                continue;
+            }
+            let Ok(service) = find_service(&label) else { continue };
+            match unload_service(service.file_path) {
+                Ok(_) => stats.started_here.store(false, Ordering::SeqCst),
+                Err(e) => eprintln!("Failed to stop idle on-demand service {}: {}", label, e),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_on_demand_status(manager: tauri::State<'_, OnDemandManager>) -> Result<Vec<OnDemandStatus>, String> {
+    let listeners = manager.listeners.lock().map_err(|e| e.to_string())?;
+    let mut statuses: Vec<OnDemandStatus> = listeners
+        .iter()
+        .map(|(label, l)| OnDemandStatus {
+            label: label.clone(),
+            port: l.port,
+            backend_port: l.config.backend_port,
+            listening: l.error.is_none(),
+            error: l.error.clone(),
+            active_connections: l.stats.active.load(Ordering::SeqCst),
+            last_activity: l.stats.last_activity.load(Ordering::SeqCst),
+            idle_timeout_secs: l.config.idle_timeout_secs,
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(statuses)
+}
+
+/// Re-read metadata and rebind on-demand listeners, e.g. after a failed bind was fixed
+#[tauri::command]
+pub fn refresh_on_demand_listeners(app: AppHandle) -> Result<String, String> {
+    let manager = app.state::<OnDemandManager>();
+    if let Ok(mut listeners) = manager.listeners.lock() {
+        // Drop failed listeners so they are retried
+        listeners.retain(|_, l| l.error.is_none());
+    }
+    sync_listeners(&app);
+    Ok("On-demand listeners refreshed".to_string())
+}
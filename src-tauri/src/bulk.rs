@@ -0,0 +1,158 @@
+//! Bulk service control: run one action over many labels with a concurrency limit.
+//!
+//! Labels are processed in dependency waves (see `dependencies::start_waves`), so a
+//! service never starts before a dependency from the same batch, and never stops
+//! after one. Dependencies outside the batch are started once before the first wave;
+//! a label whose dependencies fail is reported as failed and the rest carry on.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::{dependencies, delete_service, find_service, load_service, restart_service, unload_service};
+
+/// Used when a bulk command does not specify its own limit
+const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+    Delete,
+}
+
+/// Outcome of running an action against one label
+#[derive(Debug, Serialize, Clone)]
+pub struct LabelResult {
+    pub label: String,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BulkReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<LabelResult>,
+}
+
+impl BulkReport {
+    fn from_results(results: Vec<LabelResult>) -> Self {
+        let succeeded = results.iter().filter(|r| r.success).count();
+        Self {
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    }
+}
+
+fn run_one(label: &str, action: ServiceAction) -> Result<String, String> {
+    let service = find_service(label)?;
+    match action {
+        ServiceAction::Start if service.is_loaded => Ok("Service already running".to_string()),
+        ServiceAction::Start => load_service(service.file_path),
+        ServiceAction::Stop if !service.is_loaded => Ok("Service already stopped".to_string()),
+        ServiceAction::Stop => unload_service(service.file_path),
+        ServiceAction::Restart => restart_service(service.file_path),
+        ServiceAction::Delete => delete_service(service.file_path),
+    }
+}
+
+/// Run `f` for every label on at most `limit` worker threads, keeping input order in the output
+fn run_concurrently<F>(labels: &[String], limit: usize, f: F) -> Vec<LabelResult>
+where
+    F: Fn(&str) -> Result<String, String> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let slots: Mutex<Vec<Option<LabelResult>>> = Mutex::new(vec![None; labels.len()]);
+    let workers = limit.clamp(1, labels.len().max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(label) = labels.get(i) else { break };
+                let result = f(label);
+                let item = LabelResult {
+                    label: label.clone(),
+                    success: result.is_ok(),
+                    message: result.unwrap_or_else(|e| e),
+                };
+                if let Ok(mut slots) = slots.lock() {
+                    slots[i] = Some(item);
+                }
+            });
+        }
+    });
+
+    slots
+        .into_inner()
+        .unwrap_or_default()
+        .into_iter()
+        .zip(labels)
+        .map(|(slot, label)| {
+            slot.unwrap_or_else(|| LabelResult {
+                label: label.clone(),
+                success: false,
+                message: "Worker thread panicked".to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Run `action` on every label, wave by wave in dependency order. Labels whose
+/// dependencies can't be ordered or started fail on their own; the rest still run.
+pub(crate) fn run_action(labels: &[String], action: ServiceAction, limit: usize) -> Vec<LabelResult> {
+    let (mut waves, mut failed) = dependencies::start_waves(labels);
+    if matches!(action, ServiceAction::Stop | ServiceAction::Delete) {
+        waves.reverse();
+    } else {
+        // Shared dependencies outside the batch start once here rather than racing
+        // in every member's load
+        failed.extend(dependencies::start_batch_dependencies(&waves.concat()));
+    }
+
+    let mut results: Vec<LabelResult> = failed
+        .iter()
+        .map(|(label, error)| LabelResult {
+            label: label.clone(),
+            success: false,
+            message: error.clone(),
+        })
+        .collect();
+    for wave in waves {
+        let wave: Vec<String> = wave
+            .into_iter()
+            .filter(|label| !failed.iter().any(|(f, _)| f == label))
+            .collect();
+        results.extend(run_concurrently(&wave, limit, |label| run_one(label, action)));
+    }
+    results
+}
+
+fn run_bulk(labels: Vec<String>, action: ServiceAction, concurrency: Option<usize>) -> Result<BulkReport, String> {
+    let results = run_action(&labels, action, concurrency.unwrap_or(DEFAULT_CONCURRENCY));
+    Ok(BulkReport::from_results(results))
+}
+
+#[tauri::command(async)]
+pub fn bulk_load_services(labels: Vec<String>, concurrency: Option<usize>) -> Result<BulkReport, String> {
+    run_bulk(labels, ServiceAction::Start, concurrency)
+}
+
+#[tauri::command(async)]
+pub fn bulk_unload_services(labels: Vec<String>, concurrency: Option<usize>) -> Result<BulkReport, String> {
+    run_bulk(labels, ServiceAction::Stop, concurrency)
+}
+
+#[tauri::command(async)]
+pub fn bulk_restart_services(labels: Vec<String>, concurrency: Option<usize>) -> Result<BulkReport, String> {
+    run_bulk(labels, ServiceAction::Restart, concurrency)
+}
+
+#[tauri::command(async)]
+pub fn bulk_delete_services(labels: Vec<String>, concurrency: Option<usize>) -> Result<BulkReport, String> {
+    run_bulk(labels, ServiceAction::Delete, concurrency)
+}
@@ -0,0 +1,244 @@
+//! Crash-loop detection for KeepAlive services.
+//!
+//! launchd respawns a KeepAlive job every ThrottleInterval no matter how quickly it
+//! dies, so a broken service looks "loaded" with an ever-changing PID. A background
+//! sampler watches PID churn and exit codes; a service with too many deaths inside
+//! its window is reported as crash looping and, if its policy says so, unloaded.
+//!
+//! Restarts ServiceMaster performs itself also change the PID. They are registered
+//! with `expect_restart` beforehand and skipped by the sampler.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::monitor::{self, ServiceEvent};
+use crate::{collect_services_from, get_loaded_services, load_all_metadata, notify, unix_now, unload_service, JobState};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+/// Detection threshold for services without an explicit policy
+const DEFAULT_MAX_CRASHES: u32 = 5;
+const DEFAULT_WINDOW_MINUTES: u32 = 2;
+/// How long after a registered restart PID changes are attributed to it
+const EXPECTED_RESTART_SECS: u64 = 10;
+
+/// Plist path -> time of the last intentional (re)start
+static EXPECTED_RESTARTS: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(Mutex::default);
+
+/// Per-service crash-loop policy stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrashLoopPolicy {
+    /// Number of crashes inside the window that counts as a crash loop
+    pub max_crashes: u32,
+    pub window_minutes: u32,
+    /// Unload the service once a crash loop is detected
+    #[serde(default)]
+    pub unload: bool,
+    /// Show a desktop notification once a crash loop is detected
+    #[serde(default)]
+    pub notify: bool,
+}
+
+impl Default for CrashLoopPolicy {
+    fn default() -> Self {
+        Self {
+            max_crashes: DEFAULT_MAX_CRASHES,
+            window_minutes: DEFAULT_WINDOW_MINUTES,
+            unload: false,
+            notify: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CrashLoopStatus {
+    pub label: String,
+    pub crash_looping: bool,
+    pub crashes_in_window: usize,
+    pub window_minutes: u32,
+    pub last_exit_status: Option<i32>,
+    pub last_crash_at: Option<u64>,
+}
+
+/// Payload of the `service-crash-loop` event
+#[derive(Debug, Serialize, Clone)]
+struct CrashLoopEvent {
+    label: String,
+    crashes: usize,
+    window_minutes: u32,
+    last_exit_status: Option<i32>,
+    unloaded: bool,
+}
+
+#[derive(Default)]
+struct Track {
+    last: Option<JobState>,
+    crashes: VecDeque<u64>,
+    last_exit_status: Option<i32>,
+    window_minutes: u32,
+    looping: bool,
+}
+
+/// Managed state shared between the sampler thread and commands
+#[derive(Default)]
+pub struct CrashLoopMonitor {
+    tracks: Mutex<HashMap<String, Track>>,
+}
+
+impl CrashLoopMonitor {
+    pub(crate) fn is_looping(&self, label: &str) -> bool {
+        self.tracks
+            .lock()
+            .map(|tracks| tracks.get(label).is_some_and(|t| t.looping))
+            .unwrap_or(false)
+    }
+}
+
+/// Register an intentional load or restart so the sampler doesn't count it as a crash
+pub(crate) fn expect_restart(plist_path: &str) {
+    if let Ok(mut expected) = EXPECTED_RESTARTS.lock() {
+        let now = unix_now();
+        expected.retain(|_, at| now.saturating_sub(*at) <= EXPECTED_RESTART_SECS);
+        expected.insert(plist_path.to_string(), now);
+    }
+}
+
+fn is_expected_restart(plist_path: &str, now: u64) -> bool {
+    EXPECTED_RESTARTS.lock().is_ok_and(|expected| {
+        expected
+            .get(plist_path)
+            .is_some_and(|at| now.saturating_sub(*at) <= EXPECTED_RESTART_SECS)
+    })
+}
+
+/// Whether going from `prev` to `now` means the process died abnormally. The PID
+/// only tells that it went away (or was respawned between samples); whether that
+/// was a crash comes from the exit status launchd recorded, where a clean exit is 0
+/// and a signal is negative.
+fn is_crash(prev: &JobState, now: &JobState) -> bool {
+    let died = match (prev.pid, now.pid) {
+        (Some(a), Some(b)) => a != b,
+        (Some(_), None) => true,
+        _ => false,
+    };
+    died && now.last_exit_status.is_some_and(|s| s != 0)
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        sample(&app);
+        std::thread::sleep(SAMPLE_INTERVAL);
+    });
+}
+
+fn sample(app: &AppHandle) {
+    let jobs = get_loaded_services();
+    let services = match collect_services_from(&jobs) {
+        Ok(services) => services,
+        Err(_) => return,
+    };
+    let metadata = load_all_metadata();
+    let now = unix_now();
+
+    let mut detected = vec![];
+    let mut crashes = vec![];
+    {
+        let monitor = app.state::<CrashLoopMonitor>();
+        let Ok(mut tracks) = monitor.tracks.lock() else { return };
+
+        // Forget services that were unloaded; their history no longer applies
+        tracks.retain(|label, _| jobs.contains_key(label));
+
+        for service in services.iter().filter(|s| s.is_loaded) {
+            let Some(state) = jobs.get(&service.label) else { continue };
+            let policy = metadata
+                .get(&service.label)
+                .and_then(|m| m.crash_loop_policy.clone())
+                .unwrap_or_default();
+            let window_secs = u64::from(policy.window_minutes) * 60;
+
+            let track = tracks.entry(service.label.clone()).or_default();
+            if let Some(prev) = &track.last {
+                if is_crash(prev, state) && !is_expected_restart(&service.file_path, now) {
+                    track.crashes.push_back(now);
+                    // KeepAlive jobs are respawned after ThrottleInterval, usually a few
+                    // samples later; anything else stays down
+                    crashes.push(if service.keep_alive == Some(true) {
+                        ServiceEvent::Crashed {
+                            label: service.label.clone(),
+                            exit_status: state.last_exit_status,
+                        }
+                    } else {
+                        ServiceEvent::Exited {
+                            label: service.label.clone(),
+                            exit_code: state.last_exit_status.unwrap_or_default(),
+                        }
+                    });
+                }
+            }
+            if state.last_exit_status.is_some() {
+                track.last_exit_status = state.last_exit_status;
+            }
+            track.last = Some(*state);
+            track.window_minutes = policy.window_minutes;
+            while track.crashes.front().is_some_and(|t| now.saturating_sub(*t) > window_secs) {
+                track.crashes.pop_front();
+            }
+
+            let looping = track.crashes.len() >= policy.max_crashes.max(1) as usize;
+            if looping && !track.looping {
+                detected.push((service.clone(), policy, track.crashes.len(), track.last_exit_status));
+            }
+            track.looping = looping;
+        }
+    }
+
+    for event in crashes {
+        monitor::publish(app, event);
+    }
+
+    for (service, policy, crashes, last_exit_status) in detected {
+        let unloaded = policy.unload && unload_service(service.file_path.clone()).is_ok();
+        let name = service.display_name.clone().unwrap_or_else(|| service.label.clone());
+
+        if policy.notify {
+            let body = if unloaded {
+                format!("{} crashed {} times in {} min and was stopped", name, crashes, policy.window_minutes)
+            } else {
+                format!("{} crashed {} times in {} min", name, crashes, policy.window_minutes)
+            };
+            notify::send_for_service(app, &service.label, "Service crash loop", &body);
+        }
+
+        let _ = app.emit(
+            "service-crash-loop",
+            CrashLoopEvent {
+                label: service.label,
+                crashes,
+                window_minutes: policy.window_minutes,
+                last_exit_status,
+                unloaded,
+            },
+        );
+    }
+}
+
+#[tauri::command]
+pub fn get_crash_loop_status(monitor: tauri::State<'_, CrashLoopMonitor>) -> Result<Vec<CrashLoopStatus>, String> {
+    let tracks = monitor.tracks.lock().map_err(|e| e.to_string())?;
+    let mut statuses: Vec<CrashLoopStatus> = tracks
+        .iter()
+        .map(|(label, track)| CrashLoopStatus {
+            label: label.clone(),
+            crash_looping: track.looping,
+            crashes_in_window: track.crashes.len(),
+            window_minutes: track.window_minutes,
+            last_exit_status: track.last_exit_status,
+            last_crash_at: track.crashes.back().copied(),
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(statuses)
+}
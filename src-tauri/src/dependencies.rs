@@ -0,0 +1,401 @@
+//! Service dependency graph built from `ServiceMetadata::depends_on`.
+//!
+//! Starting a service brings its dependencies up first (and waits for them to
+//! become healthy); stopping a service takes its dependents down first.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::{
+    collect_services, launchctl_load, launchctl_unload, load_all_metadata, wait_until_healthy,
+    LaunchAgent, ServiceMetadata,
+};
+
+/// How long to wait for each dependency to become healthy before giving up
+const DEPENDENCY_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Graph = HashMap<String, Vec<String>>;
+
+fn build_graph(metadata: &HashMap<String, ServiceMetadata>) -> Graph {
+    metadata
+        .iter()
+        .map(|(label, meta)| (label.clone(), meta.depends_on.clone()))
+        .collect()
+}
+
+/// Depth-first search that returns the first cycle found, e.g. `[a, b, a]`
+fn find_cycle(graph: &Graph) -> Option<Vec<String>> {
+    fn visit(
+        node: &str,
+        graph: &Graph,
+        done: &mut HashSet<String>,
+        stack: &mut Vec<String>,
+    ) -> Option<Vec<String>> {
+        if let Some(pos) = stack.iter().position(|n| n == node) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(node.to_string());
+            return Some(cycle);
+        }
+        if done.contains(node) {
+            return None;
+        }
+        stack.push(node.to_string());
+        for dep in graph.get(node).into_iter().flatten() {
+            if let Some(cycle) = visit(dep, graph, done, stack) {
+                return Some(cycle);
+            }
+        }
+        stack.pop();
+        done.insert(node.to_string());
+        None
+    }
+
+    let mut labels: Vec<&String> = graph.keys().collect();
+    labels.sort();
+    let mut done = HashSet::new();
+    for label in labels {
+        if let Some(cycle) = visit(label, graph, &mut done, &mut vec![]) {
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+fn cycle_error(cycle: &[String]) -> String {
+    format!("Dependency cycle detected: {}", cycle.join(" → "))
+}
+
+/// Reject metadata whose `depends_on` entries form a cycle
+pub(crate) fn validate(metadata: &HashMap<String, ServiceMetadata>) -> Result<(), String> {
+    match find_cycle(&build_graph(metadata)) {
+        Some(cycle) => Err(cycle_error(&cycle)),
+        None => Ok(()),
+    }
+}
+
+/// Transitive dependencies of `label`, ordered so each one comes after its own dependencies.
+/// The label itself is not included.
+fn startup_order(graph: &Graph, label: &str) -> Result<Vec<String>, String> {
+    fn visit(
+        node: &str,
+        graph: &Graph,
+        order: &mut Vec<String>,
+        stack: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if let Some(pos) = stack.iter().position(|n| n == node) {
+            let mut cycle = stack[pos..].to_vec();
+            cycle.push(node.to_string());
+            return Err(cycle_error(&cycle));
+        }
+        if order.iter().any(|n| n == node) {
+            return Ok(());
+        }
+        stack.push(node.to_string());
+        for dep in graph.get(node).into_iter().flatten() {
+            visit(dep, graph, order, stack)?;
+        }
+        stack.pop();
+        order.push(node.to_string());
+        Ok(())
+    }
+
+    let mut order = vec![];
+    visit(label, graph, &mut order, &mut vec![])?;
+    order.pop();
+    Ok(order)
+}
+
+/// Transitive dependents of `label`, ordered so each one comes before the services it depends on.
+/// The label itself is not included.
+fn shutdown_order(graph: &Graph, label: &str) -> Vec<String> {
+    fn visit(node: &str, graph: &Graph, order: &mut Vec<String>, seen: &mut HashSet<String>) {
+        if !seen.insert(node.to_string()) {
+            return;
+        }
+        let mut dependents: Vec<&String> = graph
+            .iter()
+            .filter(|(_, deps)| deps.iter().any(|d| d == node))
+            .map(|(l, _)| l)
+            .collect();
+        dependents.sort();
+        for dependent in dependents {
+            visit(dependent, graph, order, seen);
+        }
+        order.push(node.to_string());
+    }
+
+    let mut order = vec![];
+    visit(label, graph, &mut order, &mut HashSet::new());
+    order.pop();
+    order
+}
+
+/// Split `labels` into waves that can each run concurrently. A label always lands in a
+/// later wave than any of its (transitive) dependencies from the same set, so starting
+/// waves in order and stopping them in reverse respects the graph. Labels that reach a
+/// dependency cycle can't be ordered and come back separately with the error.
+pub(crate) fn start_waves(labels: &[String]) -> (Vec<Vec<String>>, Vec<(String, String)>) {
+    let graph = build_graph(&load_all_metadata());
+    let (ordered, failed) = split_cyclic(&graph, labels);
+    (waves(&graph, &ordered), failed)
+}
+
+/// Separate the labels that reach a dependency cycle from the rest
+fn split_cyclic(graph: &Graph, labels: &[String]) -> (Vec<String>, Vec<(String, String)>) {
+    let mut ordered = vec![];
+    let mut failed = vec![];
+    for label in labels {
+        match startup_order(graph, label) {
+            Ok(_) => ordered.push(label.clone()),
+            Err(e) => failed.push((label.clone(), e)),
+        }
+    }
+    (ordered, failed)
+}
+
+/// `start_waves` over labels that don't reach a cycle
+fn waves(graph: &Graph, labels: &[String]) -> Vec<Vec<String>> {
+    fn level(
+        node: &str,
+        graph: &Graph,
+        members: &HashSet<&str>,
+        memo: &mut HashMap<String, usize>,
+    ) -> usize {
+        if let Some(l) = memo.get(node) {
+            return *l;
+        }
+        let l = graph
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|dep| level(dep, graph, members, memo) + usize::from(members.contains(dep.as_str())))
+            .max()
+            .unwrap_or(0);
+        memo.insert(node.to_string(), l);
+        l
+    }
+
+    let members: HashSet<&str> = labels.iter().map(String::as_str).collect();
+    let mut memo = HashMap::new();
+    let mut waves: Vec<Vec<String>> = vec![];
+    for label in labels {
+        let l = level(label, graph, &members, &mut memo);
+        if waves.len() <= l {
+            waves.resize(l + 1, vec![]);
+        }
+        if !waves[l].contains(label) {
+            waves[l].push(label.clone());
+        }
+    }
+    waves.retain(|wave| !wave.is_empty());
+    waves
+}
+
+/// Start `dep` unless already running and wait for it to become healthy
+fn start_one(services: &[LaunchAgent], dep: &str) -> Result<(), String> {
+    let service = services
+        .iter()
+        .find(|s| s.label == dep)
+        .ok_or_else(|| format!("Dependency '{}' was not found", dep))?;
+    if !service.is_loaded {
+        launchctl_load(&service.file_path).map_err(|e| format!("Failed to start dependency '{}': {}", dep, e))?;
+    }
+    wait_until_healthy(service, DEPENDENCY_READY_TIMEOUT)
+}
+
+/// Start every dependency of `label` that is not already running and wait for each to become healthy
+pub(crate) fn start_dependencies(label: &str) -> Result<(), String> {
+    let graph = build_graph(&load_all_metadata());
+    let order = startup_order(&graph, label)?;
+    if order.is_empty() {
+        return Ok(());
+    }
+    let services = collect_services()?;
+    order.iter().try_for_each(|dep| start_one(&services, dep))
+}
+
+/// Dependencies of a batch that are not part of it, in start order, each with the members
+/// that need it. Dependencies that themselves need a batch member are left out.
+fn batch_dependencies(graph: &Graph, labels: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut order: Vec<(String, Vec<String>)> = vec![];
+    for label in labels {
+        let Ok(deps) = startup_order(graph, label) else { continue };
+        for dep in deps {
+            if labels.contains(&dep)
+                || startup_order(graph, &dep).map_or(true, |own| own.iter().any(|d| labels.contains(d)))
+            {
+                continue;
+            }
+            match order.iter_mut().find(|(d, _)| *d == dep) {
+                Some((_, needed_by)) => needed_by.push(label.clone()),
+                None => order.push((dep, vec![label.clone()])),
+            }
+        }
+    }
+    order
+}
+
+/// Start the dependencies of a batch that are not part of it, each one once, before the batch
+/// itself starts. Dependencies that themselves need a batch member are left to the members
+/// that depend on them. Returns the members whose dependencies failed, with the error.
+pub(crate) fn start_batch_dependencies(labels: &[String]) -> Vec<(String, String)> {
+    let order = batch_dependencies(&build_graph(&load_all_metadata()), labels);
+    if order.is_empty() {
+        return vec![];
+    }
+    let services = match collect_services() {
+        Ok(services) => services,
+        Err(e) => {
+            let mut needed: Vec<String> = order.into_iter().flat_map(|(_, needed_by)| needed_by).collect();
+            needed.dedup();
+            return needed.into_iter().map(|label| (label, e.clone())).collect();
+        }
+    };
+
+    let mut failed: Vec<(String, String)> = vec![];
+    for (dep, needed_by) in order {
+        // Skip dependencies whose members all failed already, e.g. on a dependency of this one
+        let needed_by: Vec<String> = needed_by
+            .into_iter()
+            .filter(|label| !failed.iter().any(|(f, _)| f == label))
+            .collect();
+        if needed_by.is_empty() {
+            continue;
+        }
+        if let Err(e) = start_one(&services, &dep) {
+            failed.extend(needed_by.into_iter().map(|label| (label, e.clone())));
+        }
+    }
+    failed
+}
+
+/// Stop every running service that (transitively) depends on `label`
+pub(crate) fn stop_dependents(label: &str) -> Result<(), String> {
+    let graph = build_graph(&load_all_metadata());
+    let order = shutdown_order(&graph, label);
+    if order.is_empty() {
+        return Ok(());
+    }
+
+    let services = collect_services()?;
+    for dependent in order {
+        if let Some(service) = services.iter().find(|s| s.label == dependent && s.is_loaded) {
+            launchctl_unload(&service.file_path)
+                .map_err(|e| format!("Failed to stop dependent '{}': {}", dependent, e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> Graph {
+        edges
+            .iter()
+            .map(|(label, deps)| (label.to_string(), deps.iter().map(|d| d.to_string()).collect()))
+            .collect()
+    }
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    // app → api, worker; api, worker → db
+    fn diamond() -> Graph {
+        graph(&[("app", &["api", "worker"]), ("api", &["db"]), ("worker", &["db"]), ("db", &[])])
+    }
+
+    #[test]
+    fn find_cycle_reports_the_loop() {
+        assert_eq!(find_cycle(&diamond()), None);
+        let cyclic = graph(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]);
+        assert_eq!(find_cycle(&cyclic), Some(labels(&["a", "b", "c", "a"])));
+        assert_eq!(find_cycle(&graph(&[("a", &["a"])])), Some(labels(&["a", "a"])));
+    }
+
+    #[test]
+    fn find_cycle_ignores_missing_nodes() {
+        assert_eq!(find_cycle(&graph(&[("a", &["ghost"])])), None);
+    }
+
+    #[test]
+    fn startup_order_puts_shared_dependency_first_once() {
+        assert_eq!(startup_order(&diamond(), "app").unwrap(), labels(&["db", "api", "worker"]));
+        assert_eq!(startup_order(&diamond(), "db").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn startup_order_rejects_cycles() {
+        let cyclic = graph(&[("a", &["b"]), ("b", &["a"])]);
+        assert!(startup_order(&cyclic, "a").unwrap_err().contains("a → b → a"));
+    }
+
+    #[test]
+    fn startup_order_includes_missing_nodes() {
+        // Unknown dependencies are reported when starting, not dropped here
+        let g = graph(&[("a", &["ghost"])]);
+        assert_eq!(startup_order(&g, "a").unwrap(), labels(&["ghost"]));
+        assert_eq!(startup_order(&g, "unknown").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn shutdown_order_stops_dependents_first() {
+        assert_eq!(shutdown_order(&diamond(), "db"), labels(&["app", "api", "worker"]));
+        assert_eq!(shutdown_order(&diamond(), "app"), Vec::<String>::new());
+        assert_eq!(shutdown_order(&diamond(), "unknown"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn waves_follow_the_graph() {
+        let all = waves(&diamond(), &labels(&["app", "worker", "api", "db"]));
+        assert_eq!(all, vec![labels(&["db"]), labels(&["worker", "api"]), labels(&["app"])]);
+    }
+
+    #[test]
+    fn waves_only_count_members() {
+        // db is outside the batch, so api and worker start together with app after them
+        let partial = waves(&diamond(), &labels(&["app", "api", "worker"]));
+        assert_eq!(partial, vec![labels(&["api", "worker"]), labels(&["app"])]);
+        // A dependency reached only through a non-member still orders the wave
+        let chain = graph(&[("a", &["b"]), ("b", &["c"]), ("c", &[])]);
+        assert_eq!(waves(&chain, &labels(&["a", "c"])), vec![labels(&["c"]), labels(&["a"])]);
+    }
+
+    #[test]
+    fn split_cyclic_fails_only_labels_reaching_a_cycle() {
+        let g = graph(&[("a", &["b"]), ("b", &["a"]), ("c", &["a"]), ("d", &["e"]), ("e", &[])]);
+        let (ordered, failed) = split_cyclic(&g, &labels(&["c", "d", "e"]));
+        assert_eq!(ordered, labels(&["d", "e"]));
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "c");
+        assert!(failed[0].1.contains("a → b → a"));
+    }
+
+    #[test]
+    fn batch_dependencies_are_shared_and_ordered() {
+        let needed = batch_dependencies(&diamond(), &labels(&["api", "worker"]));
+        assert_eq!(needed, vec![("db".to_string(), labels(&["api", "worker"]))]);
+
+        let needed = batch_dependencies(&diamond(), &labels(&["app"]));
+        let deps: Vec<&str> = needed.iter().map(|(d, _)| d.as_str()).collect();
+        assert_eq!(deps, ["db", "api", "worker"]);
+    }
+
+    #[test]
+    fn batch_dependencies_skip_members_and_what_needs_them() {
+        // worker is outside but needs db from the batch, so app's own start handles it
+        let needed = batch_dependencies(&diamond(), &labels(&["app", "db"]));
+        assert!(needed.is_empty());
+        // Unknown dependencies are kept so starting them reports the error
+        let g = graph(&[("a", &["ghost"]), ("b", &["ghost"])]);
+        assert_eq!(batch_dependencies(&g, &labels(&["a", "b"])), vec![("ghost".to_string(), labels(&["a", "b"]))]);
+    }
+
+    #[test]
+    fn waves_handle_missing_nodes_and_duplicates() {
+        let g = graph(&[("a", &["ghost"])]);
+        assert_eq!(waves(&g, &labels(&["a", "unknown", "a"])), vec![labels(&["a", "unknown"])]);
+    }
+}
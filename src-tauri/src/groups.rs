@@ -0,0 +1,165 @@
+//! Named service groups ("AI stack", "Work DB", ...) that start and stop together.
+//!
+//! Groups are stored in `groups.json` next to `metadata.json`. Members are started
+//! wave by wave following their dependencies, with every member of a wave running
+//! concurrently.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::bulk::{self, LabelResult, ServiceAction};
+use crate::{collect_services, get_metadata_file_path, is_service_healthy, tray};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServiceGroup {
+    pub name: String,
+    pub labels: Vec<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MemberStatus {
+    pub label: String,
+    pub display_name: Option<String>,
+    pub is_loaded: bool,
+    pub pid: Option<i32>,
+    /// None when the member is stopped or could not be found
+    pub healthy: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GroupStatus {
+    pub name: String,
+    pub running: usize,
+    pub total: usize,
+    pub members: Vec<MemberStatus>,
+}
+
+fn get_groups_file_path() -> PathBuf {
+    get_metadata_file_path().with_file_name("groups.json")
+}
+
+pub(crate) fn load_groups() -> Vec<ServiceGroup> {
+    let path = get_groups_file_path();
+    if path.exists() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(groups) = serde_json::from_str(&content) {
+                return groups;
+            }
+        }
+    }
+    vec![]
+}
+
+fn save_groups(groups: &[ServiceGroup]) -> Result<(), String> {
+    let path = get_groups_file_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(groups).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn find_group(name: &str) -> Result<ServiceGroup, String> {
+    load_groups()
+        .into_iter()
+        .find(|g| g.name == name)
+        .ok_or_else(|| format!("Group '{}' not found", name))
+}
+
+/// Run `action` on every member of a group and collect one result per member
+pub(crate) fn run_group_action(name: &str, action: ServiceAction) -> Result<Vec<LabelResult>, String> {
+    let group = find_group(name)?;
+    // Members of a wave all run at once; groups are small and users expect the whole stack to move together
+    Ok(bulk::run_action(&group.labels, action, group.labels.len()))
+}
+
+#[tauri::command]
+pub fn get_groups() -> Result<Vec<ServiceGroup>, String> {
+    Ok(load_groups())
+}
+
+/// Create a group, or replace the group with the same name
+#[tauri::command]
+pub fn save_group(app: tauri::AppHandle, group: ServiceGroup) -> Result<String, String> {
+    if group.name.trim().is_empty() {
+        return Err("Group name cannot be empty".to_string());
+    }
+    let mut groups = load_groups();
+    match groups.iter_mut().find(|g| g.name == group.name) {
+        Some(existing) => *existing = group,
+        None => groups.push(group),
+    }
+    save_groups(&groups)?;
+    tray::refresh(&app);
+    Ok("Group saved successfully".to_string())
+}
+
+#[tauri::command]
+pub fn delete_group(app: tauri::AppHandle, name: String) -> Result<String, String> {
+    let mut groups = load_groups();
+    let before = groups.len();
+    groups.retain(|g| g.name != name);
+    if groups.len() == before {
+        return Err(format!("Group '{}' not found", name));
+    }
+    save_groups(&groups)?;
+    tray::refresh(&app);
+    Ok("Group deleted successfully".to_string())
+}
+
+#[tauri::command(async)]
+pub fn start_group(app: tauri::AppHandle, name: String) -> Result<Vec<LabelResult>, String> {
+    let results = run_group_action(&name, ServiceAction::Start);
+    tray::refresh(&app);
+    results
+}
+
+#[tauri::command(async)]
+pub fn stop_group(app: tauri::AppHandle, name: String) -> Result<Vec<LabelResult>, String> {
+    let results = run_group_action(&name, ServiceAction::Stop);
+    tray::refresh(&app);
+    results
+}
+
+#[tauri::command(async)]
+pub fn restart_group(app: tauri::AppHandle, name: String) -> Result<Vec<LabelResult>, String> {
+    let results = run_group_action(&name, ServiceAction::Restart);
+    tray::refresh(&app);
+    results
+}
+
+#[tauri::command(async)]
+pub fn get_group_status(name: String) -> Result<GroupStatus, String> {
+    let group = find_group(&name)?;
+    let services = collect_services()?;
+
+    let members: Vec<MemberStatus> = std::thread::scope(|scope| {
+        let handles: Vec<_> = group
+            .labels
+            .iter()
+            .map(|label| {
+                let service = services.iter().find(|s| &s.label == label);
+                scope.spawn(move || MemberStatus {
+                    label: label.clone(),
+                    display_name: service.and_then(|s| s.display_name.clone()),
+                    is_loaded: service.is_some_and(|s| s.is_loaded),
+                    pid: service.and_then(|s| s.pid),
+                    healthy: service
+                        .filter(|s| s.is_loaded)
+                        .map(is_service_healthy),
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    });
+
+    Ok(GroupStatus {
+        name: group.name,
+        running: members.iter().filter(|m| m.is_loaded).count(),
+        total: members.len(),
+        members,
+    })
+}
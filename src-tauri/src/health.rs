@@ -0,0 +1,1118 @@
+//! Port and HTTP health checks.
+//!
+//! Port checks default to a TCP connect on loopback (IPv4, then IPv6); a
+//! `PortCheckSpec` can point them at another host, pin the address family or
+//! switch to UDP.
+//!
+//! A bare `health_url` keeps the old behaviour (any 2xx/3xx is healthy). A
+//! `HealthCheckSpec` in `ServiceMetadata` can tighten that with a method, headers,
+//! expected status codes and assertions on the response body.
+//!
+//! Services that only listen on a Unix domain socket get a `SocketCheckSpec`,
+//! optionally with an HTTP request sent over the socket.
+//!
+//! Services without any network endpoint can use a `CommandCheckSpec` instead:
+//! a shell command run in the service's working directory and environment.
+//!
+//! Every check produces a `HealthResult` that says not only whether the service
+//! is up but also how long the check took and, on failure, why it failed.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::{unix_now, LaunchAgent};
+
+const DEFAULT_HTTP_TIMEOUT_MS: u64 = 2000;
+const DEFAULT_PORT_TIMEOUT_MS: u64 = 500;
+const DEFAULT_COMMAND_TIMEOUT_MS: u64 = 5000;
+/// Captured command output kept in the result
+const COMMAND_OUTPUT_LIMIT: usize = 4096;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    /// Responding correctly, but slower than the configured latency budget
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    Dns,
+    ConnectionRefused,
+    Timeout,
+    Tls,
+    BadStatus,
+    AssertionFailed,
+    InvalidConfig,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HealthResult {
+    pub status: HealthStatus,
+    pub http_status: Option<u16>,
+    pub latency_ms: u64,
+    /// None when the check passed
+    pub failure: Option<FailureCategory>,
+    pub message: String,
+    /// Unix timestamp (seconds) when the check ran
+    pub checked_at: u64,
+    /// Captured stdout/stderr of command checks
+    pub output: Option<String>,
+}
+
+impl HealthResult {
+    pub(crate) fn healthy(latency_ms: u64, http_status: Option<u16>, message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            http_status,
+            latency_ms,
+            failure: None,
+            message: message.into(),
+            checked_at: unix_now(),
+            output: None,
+        }
+    }
+
+    pub(crate) fn unhealthy(latency_ms: u64, http_status: Option<u16>, failure: CheckFailure) -> Self {
+        Self {
+            status: HealthStatus::Unhealthy,
+            http_status,
+            latency_ms,
+            failure: Some(failure.category),
+            message: failure.message,
+            checked_at: unix_now(),
+            output: None,
+        }
+    }
+
+    /// Healthy or degraded; a degraded service still answers correctly
+    pub fn is_up(&self) -> bool {
+        self.status != HealthStatus::Unhealthy
+    }
+}
+
+/// Why a check failed, before timing is attached
+pub(crate) struct CheckFailure {
+    pub category: FailureCategory,
+    pub message: String,
+}
+
+impl CheckFailure {
+    pub(crate) fn new(category: FailureCategory, message: impl Into<String>) -> Self {
+        Self {
+            category,
+            message: message.into(),
+        }
+    }
+}
+
+/// Classify a connection error by its kind and, for wrapped errors, its message
+pub(crate) fn classify_io_error(e: &std::io::Error) -> FailureCategory {
+    use std::io::ErrorKind;
+    match e.kind() {
+        ErrorKind::ConnectionRefused => FailureCategory::ConnectionRefused,
+        ErrorKind::TimedOut | ErrorKind::WouldBlock => FailureCategory::Timeout,
+        _ => classify_message(&e.to_string()),
+    }
+}
+
+fn classify_message(message: &str) -> FailureCategory {
+    let lower = message.to_lowercase();
+    if lower.contains("dns") || lower.contains("lookup") || lower.contains("resolve") || lower.contains("nodename") {
+        FailureCategory::Dns
+    } else if lower.contains("refused") {
+        FailureCategory::ConnectionRefused
+    } else if lower.contains("timed out") || lower.contains("timeout") {
+        FailureCategory::Timeout
+    } else if lower.contains("tls") || lower.contains("ssl") || lower.contains("certificate") {
+        FailureCategory::Tls
+    } else {
+        FailureCategory::Other
+    }
+}
+
+/// Asserts that the value at `path` (e.g. `status` or `data.checks[0].ok`) equals `equals`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct JsonAssertion {
+    pub path: String,
+    pub equals: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HealthCheckSpec {
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Request body, e.g. for POST-based health endpoints
+    pub body: Option<String>,
+    /// Accepted status codes; empty means any 2xx/3xx
+    #[serde(default)]
+    pub expected_status: Vec<u16>,
+    pub body_contains: Option<String>,
+    pub body_regex: Option<String>,
+    #[serde(default)]
+    pub json_assertions: Vec<JsonAssertion>,
+    pub timeout_ms: Option<u64>,
+    #[serde(default = "default_verify_tls")]
+    pub verify_tls: bool,
+    /// Responses slower than this are reported as degraded
+    pub degraded_latency_ms: Option<u64>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_verify_tls() -> bool {
+    true
+}
+
+impl Default for HealthCheckSpec {
+    fn default() -> Self {
+        Self {
+            method: default_method(),
+            headers: HashMap::new(),
+            body: None,
+            expected_status: vec![],
+            body_contains: None,
+            body_regex: None,
+            json_assertions: vec![],
+            timeout_ms: None,
+            verify_tls: default_verify_tls(),
+            degraded_latency_ms: None,
+        }
+    }
+}
+
+impl HealthCheckSpec {
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_HTTP_TIMEOUT_MS))
+    }
+
+    /// Whether the body must be read at all
+    fn inspects_body(&self) -> bool {
+        self.body_contains.is_some() || self.body_regex.is_some() || !self.json_assertions.is_empty()
+    }
+}
+
+struct HttpResponse {
+    status: u16,
+    body: String,
+}
+
+#[cfg(feature = "native-tls")]
+fn classify_reqwest_error(e: &reqwest::Error) -> CheckFailure {
+    // The useful detail ("dns error", "Connection refused", ...) sits in the source chain
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(inner) = source {
+        message = format!("{}: {}", message, inner);
+        source = inner.source();
+    }
+    let category = if e.is_timeout() {
+        FailureCategory::Timeout
+    } else {
+        classify_message(&message)
+    };
+    CheckFailure::new(category, message)
+}
+
+#[cfg(feature = "native-tls")]
+fn fetch_with_reqwest(url: &str, spec: &HealthCheckSpec) -> Result<HttpResponse, CheckFailure> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(spec.timeout())
+        .danger_accept_invalid_certs(!spec.verify_tls)
+        .build()
+        .map_err(|e| CheckFailure::new(FailureCategory::Other, e.to_string()))?;
+    let method = reqwest::Method::from_bytes(spec.method.to_uppercase().as_bytes()).map_err(|_| {
+        CheckFailure::new(FailureCategory::InvalidConfig, format!("Invalid HTTP method '{}'", spec.method))
+    })?;
+
+    let mut request = client.request(method, url);
+    for (name, value) in &spec.headers {
+        request = request.header(name, value);
+    }
+    if let Some(body) = &spec.body {
+        request = request.body(body.clone());
+    }
+
+    let response = request.send().map_err(|e| classify_reqwest_error(&e))?;
+    let status = response.status().as_u16();
+    let body = if spec.inspects_body() {
+        response.text().map_err(|e| classify_reqwest_error(&e))?
+    } else {
+        String::new()
+    };
+    Ok(HttpResponse { status, body })
+}
+
+#[cfg(all(not(feature = "native-tls"), any(target_os = "macos", target_os = "linux")))]
+fn fetch_with_curl(url: &str, spec: &HealthCheckSpec) -> Result<HttpResponse, CheckFailure> {
+    let mut args: Vec<String> = vec![
+        "-s".to_string(),
+        "-X".to_string(),
+        spec.method.to_uppercase(),
+        "-m".to_string(),
+        format!("{:.1}", spec.timeout().as_secs_f64()),
+        "-w".to_string(),
+        "\n%{http_code}".to_string(),
+    ];
+    if !spec.verify_tls {
+        args.push("-k".to_string());
+    }
+    for (name, value) in &spec.headers {
+        args.push("-H".to_string());
+        args.push(format!("{}: {}", name, value));
+    }
+    if let Some(body) = &spec.body {
+        args.push("--data-raw".to_string());
+        args.push(body.clone());
+    }
+    args.push(url.to_string());
+
+    let output = Command::new("curl")
+        .args(&args)
+        .output()
+        .map_err(|e| CheckFailure::new(FailureCategory::Other, e.to_string()))?;
+    // See "EXIT CODES" in curl(1)
+    let category = match output.status.code() {
+        Some(0) => None,
+        Some(6) => Some(FailureCategory::Dns),
+        Some(7) => Some(FailureCategory::ConnectionRefused),
+        Some(28) => Some(FailureCategory::Timeout),
+        Some(35 | 51 | 53 | 54 | 58 | 59 | 60 | 64 | 66 | 77 | 80 | 82 | 83 | 90 | 91) => Some(FailureCategory::Tls),
+        _ => Some(FailureCategory::Other),
+    };
+    if let Some(category) = category {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        let message = if stderr.is_empty() {
+            format!("curl exited with {}", output.status)
+        } else {
+            stderr
+        };
+        return Err(CheckFailure::new(category, message));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    let status: u16 = status
+        .trim()
+        .parse()
+        .map_err(|_| CheckFailure::new(FailureCategory::Other, "Request failed"))?;
+    Ok(HttpResponse {
+        status,
+        body: body.to_string(),
+    })
+}
+
+fn fetch(url: &str, spec: &HealthCheckSpec) -> Result<HttpResponse, CheckFailure> {
+    // Use reqwest when TLS support is compiled in (cross-platform)
+    #[cfg(feature = "native-tls")]
+    {
+        fetch_with_reqwest(url, spec)
+    }
+
+    // Fallback to curl on Unix systems
+    #[cfg(all(not(feature = "native-tls"), any(target_os = "macos", target_os = "linux")))]
+    {
+        fetch_with_curl(url, spec)
+    }
+
+    #[cfg(all(not(feature = "native-tls"), target_os = "windows"))]
+    {
+        let _ = (url, spec);
+        Err(CheckFailure::new(FailureCategory::Other, "No HTTP client available"))
+    }
+}
+
+/// Look up a dotted path with optional array indices, e.g. `$.data.items[0].ok`
+pub(crate) fn lookup_json_path<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    let path = path.trim_start_matches('$').trim_start_matches('.');
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indices) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indices.split('[').filter(|s| !s.is_empty()) {
+            let index: usize = index.trim_end_matches(']').parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+/// Check a response against the spec, returning the first failed expectation
+fn evaluate(response: &HttpResponse, spec: &HealthCheckSpec) -> Result<(), CheckFailure> {
+    let status_ok = if spec.expected_status.is_empty() {
+        (200..400).contains(&response.status)
+    } else {
+        spec.expected_status.contains(&response.status)
+    };
+    if !status_ok {
+        return Err(CheckFailure::new(
+            FailureCategory::BadStatus,
+            format!("Unexpected status {}", response.status),
+        ));
+    }
+
+    let failed = |message: String| CheckFailure::new(FailureCategory::AssertionFailed, message);
+
+    if let Some(needle) = &spec.body_contains {
+        if !response.body.contains(needle.as_str()) {
+            return Err(failed(format!("Body does not contain '{}'", needle)));
+        }
+    }
+
+    if let Some(pattern) = &spec.body_regex {
+        let re = Regex::new(pattern)
+            .map_err(|e| CheckFailure::new(FailureCategory::InvalidConfig, format!("Invalid body_regex: {}", e)))?;
+        if !re.is_match(&response.body) {
+            return Err(failed(format!("Body does not match /{}/", pattern)));
+        }
+    }
+
+    if !spec.json_assertions.is_empty() {
+        let json: serde_json::Value = serde_json::from_str(&response.body)
+            .map_err(|e| failed(format!("Body is not valid JSON: {}", e)))?;
+        for assertion in &spec.json_assertions {
+            match lookup_json_path(&json, &assertion.path) {
+                Some(actual) if *actual == assertion.equals => {}
+                Some(actual) => {
+                    return Err(failed(format!("{} is {}, expected {}", assertion.path, actual, assertion.equals)))
+                }
+                None => return Err(failed(format!("{} is missing", assertion.path))),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run an HTTP health check against `url`
+pub(crate) fn run_http_check(url: &str, spec: &HealthCheckSpec) -> HealthResult {
+    let start = Instant::now();
+    let response = fetch(url, spec);
+    judge_response(response, start.elapsed().as_millis() as u64, spec)
+}
+
+/// Turn a fetched response into a result, applying the spec's expectations and latency budget
+fn judge_response(response: Result<HttpResponse, CheckFailure>, latency_ms: u64, spec: &HealthCheckSpec) -> HealthResult {
+    let response = match response {
+        Ok(response) => response,
+        Err(failure) => return HealthResult::unhealthy(latency_ms, None, failure),
+    };
+    if let Err(failure) = evaluate(&response, spec) {
+        return HealthResult::unhealthy(latency_ms, Some(response.status), failure);
+    }
+
+    let mut result = HealthResult::healthy(latency_ms, Some(response.status), format!("HTTP {}", response.status));
+    if spec.degraded_latency_ms.is_some_and(|budget| latency_ms > budget) {
+        result.status = HealthStatus::Degraded;
+        result.message = format!("HTTP {} but slow ({} ms)", response.status, latency_ms);
+    }
+    result
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PortProtocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    /// Try every resolved address, IPv4 first
+    #[default]
+    Any,
+    Ipv4,
+    Ipv6,
+}
+
+/// Where and how to probe `port`; stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PortCheckSpec {
+    /// Hostname or IP; defaults to loopback (`127.0.0.1` and `::1`)
+    pub host: Option<String>,
+    #[serde(default)]
+    pub family: AddressFamily,
+    #[serde(default)]
+    pub protocol: PortProtocol,
+    /// Per-address timeout; defaults to 500ms
+    pub timeout_ms: Option<u64>,
+}
+
+impl PortCheckSpec {
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_PORT_TIMEOUT_MS))
+    }
+
+    /// Addresses to try, in order
+    fn targets(&self, port: u16) -> Result<Vec<SocketAddr>, CheckFailure> {
+        let mut addrs: Vec<SocketAddr> = match &self.host {
+            None => vec![
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+                SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), port),
+            ],
+            Some(host) => (host.as_str(), port)
+                .to_socket_addrs()
+                .map_err(|e| CheckFailure::new(FailureCategory::Dns, format!("Cannot resolve {}: {}", host, e)))?
+                .collect(),
+        };
+        addrs.retain(|addr| match self.family {
+            AddressFamily::Any => true,
+            AddressFamily::Ipv4 => addr.is_ipv4(),
+            AddressFamily::Ipv6 => addr.is_ipv6(),
+        });
+        // Dual-stack: prefer IPv4 and fall back to IPv6
+        addrs.sort_by_key(|addr| addr.is_ipv6());
+        if addrs.is_empty() {
+            return Err(CheckFailure::new(
+                FailureCategory::Dns,
+                format!("No {:?} address for {}", self.family, self.host.as_deref().unwrap_or("localhost")),
+            ));
+        }
+        Ok(addrs)
+    }
+}
+
+fn probe_tcp(addr: &SocketAddr, timeout: Duration) -> Result<String, CheckFailure> {
+    TcpStream::connect_timeout(addr, timeout)
+        .map(|_| format!("{} is open", addr))
+        .map_err(|e| CheckFailure::new(classify_io_error(&e), format!("{}: {}", addr, e)))
+}
+
+/// UDP has no handshake: send an empty datagram and treat an ICMP "port
+/// unreachable" (surfacing as ConnectionRefused) as closed. Silence means the
+/// port is open but the service ignores unknown packets, as WireGuard does.
+fn probe_udp(addr: &SocketAddr, timeout: Duration) -> Result<String, CheckFailure> {
+    let io_failure = |e: std::io::Error| CheckFailure::new(classify_io_error(&e), format!("{}: {}", addr, e));
+    let bind_addr: SocketAddr = if addr.is_ipv6() {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind_addr).map_err(io_failure)?;
+    socket.connect(addr).map_err(io_failure)?;
+    socket.set_read_timeout(Some(timeout)).map_err(io_failure)?;
+    socket.send(&[]).map_err(io_failure)?;
+
+    let mut buf = [0u8; 512];
+    match socket.recv(&mut buf) {
+        Ok(_) => Ok(format!("{}/udp replied", addr)),
+        Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+            Ok(format!("{}/udp is open (no reply)", addr))
+        }
+        Err(e) => Err(io_failure(e)),
+    }
+}
+
+/// Probe a port, trying each target address until one answers
+pub(crate) fn run_port_check(port: u16, spec: &PortCheckSpec) -> HealthResult {
+    let start = Instant::now();
+    let targets = match spec.targets(port) {
+        Ok(targets) => targets,
+        Err(failure) => return HealthResult::unhealthy(start.elapsed().as_millis() as u64, None, failure),
+    };
+
+    let mut failures: Vec<CheckFailure> = vec![];
+    for addr in &targets {
+        let probed = match spec.protocol {
+            PortProtocol::Tcp => probe_tcp(addr, spec.timeout()),
+            PortProtocol::Udp => probe_udp(addr, spec.timeout()),
+        };
+        match probed {
+            Ok(message) => return HealthResult::healthy(start.elapsed().as_millis() as u64, None, message),
+            Err(failure) => failures.push(failure),
+        }
+    }
+
+    // Report the first address's category but list every attempt
+    let message = failures.iter().map(|f| f.message.as_str()).collect::<Vec<_>>().join("; ");
+    let category = failures.first().map_or(FailureCategory::Other, |f| f.category);
+    HealthResult::unhealthy(
+        start.elapsed().as_millis() as u64,
+        None,
+        CheckFailure::new(category, message),
+    )
+}
+
+/// Shell command whose exit code (and optionally stdout) decides health
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommandCheckSpec {
+    /// Run through `sh -c` (`cmd /C` on Windows), e.g. `redis-cli ping`
+    pub command: String,
+    #[serde(default)]
+    pub expected_exit_code: i32,
+    /// Healthy only if stdout contains this text
+    pub stdout_contains: Option<String>,
+    pub timeout_ms: Option<u64>,
+}
+
+/// Keep the tail of captured output, respecting char boundaries
+fn truncate_output(output: &str) -> String {
+    let output = output.trim();
+    if output.len() <= COMMAND_OUTPUT_LIMIT {
+        return output.to_string();
+    }
+    let mut start = output.len() - COMMAND_OUTPUT_LIMIT;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("…{}", &output[start..])
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buf = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
+
+/// Kill a command check and everything it spawned
+fn kill_process_group(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    {
+        // The child leads its own group (`process_group(0)`), so its PID is the group ID
+        if unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } == 0 {
+            return;
+        }
+    }
+    let _ = child.kill();
+}
+
+/// Run a command check with the service's working directory and environment
+pub(crate) fn run_command_check(
+    spec: &CommandCheckSpec,
+    working_directory: Option<&str>,
+    env: Option<&HashMap<String, String>>,
+) -> HealthResult {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", &spec.command]);
+        command
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        use std::os::unix::process::CommandExt;
+        let mut command = Command::new("sh");
+        // Own process group, so a timeout can kill whatever the shell started too
+        command.args(["-c", &spec.command]).process_group(0);
+        command
+    };
+    if let Some(dir) = working_directory {
+        command.current_dir(dir);
+    }
+    if let Some(env) = env {
+        command.envs(env);
+    }
+
+    let start = Instant::now();
+    let latency = |start: Instant| start.elapsed().as_millis() as u64;
+    let mut child = match command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            let category = if e.kind() == std::io::ErrorKind::NotFound {
+                FailureCategory::InvalidConfig
+            } else {
+                FailureCategory::Other
+            };
+            return HealthResult::unhealthy(latency(start), None, CheckFailure::new(category, e.to_string()));
+        }
+    };
+
+    // Drain the pipes on their own threads so a chatty command can't block on a full pipe
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let timeout = Duration::from_millis(spec.timeout_ms.unwrap_or(DEFAULT_COMMAND_TIMEOUT_MS));
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if start.elapsed() >= timeout => {
+                kill_process_group(&mut child);
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(_) => break None,
+        }
+    };
+    let latency_ms = latency(start);
+
+    // After a timeout a grandchild that left the group may still hold the pipes open, so don't wait on them
+    let (stdout, stderr) = match status {
+        Some(_) => (stdout.join().unwrap_or_default(), stderr.join().unwrap_or_default()),
+        None => (String::new(), String::new()),
+    };
+    let output = truncate_output(&format!("{}\n{}", stdout.trim_end(), stderr.trim_end()));
+
+    let verdict = match status {
+        None => Err(CheckFailure::new(
+            FailureCategory::Timeout,
+            format!("Command did not finish within {} ms", timeout.as_millis()),
+        )),
+        Some(status) if status.code() != Some(spec.expected_exit_code) => Err(CheckFailure::new(
+            FailureCategory::BadStatus,
+            format!("Command exited with {}, expected {}", status, spec.expected_exit_code),
+        )),
+        Some(_) => match &spec.stdout_contains {
+            Some(needle) if !stdout.contains(needle.as_str()) => Err(CheckFailure::new(
+                FailureCategory::AssertionFailed,
+                format!("Output does not contain '{}'", needle),
+            )),
+            _ => Ok(()),
+        },
+    };
+
+    let mut result = match verdict {
+        Ok(()) => HealthResult::healthy(latency_ms, None, format!("`{}` succeeded", spec.command)),
+        Err(failure) => HealthResult::unhealthy(latency_ms, None, failure),
+    };
+    result.output = Some(output);
+    result
+}
+
+/// Unix domain socket to connect to; stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SocketCheckSpec {
+    pub path: String,
+    /// Send `GET <http_path>` over the socket, e.g. `/_ping` for Docker
+    pub http_path: Option<String>,
+    /// Expectations for the HTTP response; without it any 2xx/3xx counts as healthy
+    pub http: Option<HealthCheckSpec>,
+}
+
+/// Decode a `Transfer-Encoding: chunked` body, stopping at the last complete chunk
+#[cfg(unix)]
+fn decode_chunked(mut body: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    while let Some(line_end) = body.windows(2).position(|w| w == b"\r\n") {
+        let size = String::from_utf8_lossy(&body[..line_end]);
+        let Ok(size) = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16) else { break };
+        let data = &body[line_end + 2..];
+        if size == 0 || data.len() < size {
+            break;
+        }
+        out.extend_from_slice(&data[..size]);
+        body = data[size..].strip_prefix(b"\r\n").unwrap_or(&data[size..]);
+    }
+    out
+}
+
+/// Minimal HTTP/1.1 client over a Unix socket
+#[cfg(unix)]
+fn fetch_over_socket(path: &str, request_path: &str, spec: &HealthCheckSpec) -> Result<HttpResponse, CheckFailure> {
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+
+    let io_failure = |e: std::io::Error| CheckFailure::new(classify_io_error(&e), format!("{}: {}", path, e));
+    let mut stream = UnixStream::connect(path).map_err(io_failure)?;
+    stream.set_read_timeout(Some(spec.timeout())).map_err(io_failure)?;
+    stream.set_write_timeout(Some(spec.timeout())).map_err(io_failure)?;
+
+    let body = spec.body.as_deref().unwrap_or("");
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n",
+        spec.method.to_uppercase(),
+        request_path
+    );
+    for (name, value) in &spec.headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if !body.is_empty() {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).map_err(io_failure)?;
+
+    let mut raw = vec![];
+    stream.read_to_end(&mut raw).map_err(io_failure)?;
+    parse_http_response(&raw)
+}
+
+/// Split a raw HTTP/1.1 response into its status and (de-chunked) body
+#[cfg(unix)]
+fn parse_http_response(raw: &[u8]) -> Result<HttpResponse, CheckFailure> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| CheckFailure::new(FailureCategory::Other, "Incomplete HTTP response"))?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let status: u16 = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| CheckFailure::new(FailureCategory::Other, "Malformed HTTP status line"))?;
+    let chunked = head
+        .lines()
+        .any(|line| line.to_lowercase().replace(' ', "") == "transfer-encoding:chunked");
+
+    let body = &raw[header_end + 4..];
+    let body = if chunked { decode_chunked(body) } else { body.to_vec() };
+    Ok(HttpResponse {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+/// Connect to a Unix socket and, if configured, make an HTTP request over it
+pub(crate) fn run_socket_check(spec: &SocketCheckSpec) -> HealthResult {
+    let start = Instant::now();
+
+    #[cfg(unix)]
+    {
+        let http_spec = spec.http.clone().unwrap_or_default();
+        match &spec.http_path {
+            Some(request_path) => {
+                let response = fetch_over_socket(&spec.path, request_path, &http_spec);
+                judge_response(response, start.elapsed().as_millis() as u64, &http_spec)
+            }
+            None => match std::os::unix::net::UnixStream::connect(&spec.path) {
+                Ok(_) => HealthResult::healthy(
+                    start.elapsed().as_millis() as u64,
+                    None,
+                    format!("{} is accepting connections", spec.path),
+                ),
+                Err(e) => HealthResult::unhealthy(
+                    start.elapsed().as_millis() as u64,
+                    None,
+                    CheckFailure::new(classify_io_error(&e), format!("{}: {}", spec.path, e)),
+                ),
+            },
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        HealthResult::unhealthy(
+            start.elapsed().as_millis() as u64,
+            None,
+            CheckFailure::new(
+                FailureCategory::InvalidConfig,
+                format!("Unix sockets are not supported on this platform ({})", spec.path),
+            ),
+        )
+    }
+}
+
+/// Results of every check configured for a service
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ServiceChecks {
+    pub port: Option<HealthResult>,
+    pub http: Option<HealthResult>,
+    pub command: Option<HealthResult>,
+    pub probe: Option<HealthResult>,
+    pub socket: Option<HealthResult>,
+}
+
+impl ServiceChecks {
+    /// The worst individual result, or None when no check is configured
+    pub fn summary(&self) -> Option<&HealthResult> {
+        fn rank(status: HealthStatus) -> u8 {
+            match status {
+                HealthStatus::Healthy => 0,
+                HealthStatus::Degraded => 1,
+                HealthStatus::Unhealthy => 2,
+            }
+        }
+        [
+            self.port.as_ref(),
+            self.http.as_ref(),
+            self.command.as_ref(),
+            self.probe.as_ref(),
+            self.socket.as_ref(),
+        ]
+            .into_iter()
+            .flatten()
+            .max_by_key(|r| rank(r.status))
+    }
+}
+
+/// Run every check configured for a service
+pub(crate) fn check_service(agent: &LaunchAgent) -> ServiceChecks {
+    ServiceChecks {
+        port: agent.service_port().map(|port| {
+            let spec = agent.port_check.clone().unwrap_or_default();
+            run_port_check(port, &spec)
+        }),
+        http: agent.health_url.as_ref().map(|url| {
+            let spec = agent.health_check.clone().unwrap_or_default();
+            run_http_check(url, &spec)
+        }),
+        command: agent.command_check.as_ref().map(|spec| {
+            run_command_check(
+                spec,
+                agent.working_directory.as_deref(),
+                agent.environment_variables.as_ref(),
+            )
+        }),
+        probe: agent.probe.as_ref().map(|spec| crate::probes::run_probe(spec, agent)),
+        socket: agent.socket_check.as_ref().map(run_socket_check),
+    }
+}
+
+#[tauri::command]
+pub fn check_port(port: u16, spec: Option<PortCheckSpec>) -> Result<HealthResult, String> {
+    Ok(run_port_check(port, &spec.unwrap_or_default()))
+}
+
+/// Run a command check for a service, using its plist's working directory and environment
+#[tauri::command(async)]
+pub fn check_command(label: String, spec: Option<CommandCheckSpec>) -> Result<HealthResult, String> {
+    let service = crate::find_service(&label)?;
+    let spec = spec
+        .or_else(|| service.command_check.clone())
+        .ok_or_else(|| format!("No command check configured for {}", label))?;
+    Ok(run_command_check(
+        &spec,
+        service.working_directory.as_deref(),
+        service.environment_variables.as_ref(),
+    ))
+}
+
+#[tauri::command]
+pub fn check_health(url: String, spec: Option<HealthCheckSpec>) -> Result<HealthResult, String> {
+    Ok(run_http_check(&url, &spec.unwrap_or_default()))
+}
+
+#[tauri::command(async)]
+pub fn check_socket(spec: SocketCheckSpec) -> Result<HealthResult, String> {
+    Ok(run_socket_check(&spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(status: u16, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            body: body.to_string(),
+        }
+    }
+
+    fn failure_of(result: Result<(), CheckFailure>) -> Option<FailureCategory> {
+        result.err().map(|failure| failure.category)
+    }
+
+    #[test]
+    fn json_path_lookup() {
+        let value = json!({"status": "ok", "data": {"checks": [{"ok": true}, {"ok": false}]}, "list": [[1, 2]]});
+        assert_eq!(lookup_json_path(&value, "status"), Some(&json!("ok")));
+        assert_eq!(lookup_json_path(&value, "$.data.checks[1].ok"), Some(&json!(false)));
+        assert_eq!(lookup_json_path(&value, "list[0][1]"), Some(&json!(2)));
+        assert_eq!(lookup_json_path(&value, "$"), Some(&value));
+    }
+
+    #[test]
+    fn json_path_lookup_missing() {
+        let value = json!({"data": {"checks": [{"ok": true}]}});
+        assert_eq!(lookup_json_path(&value, "status"), None);
+        assert_eq!(lookup_json_path(&value, "data.checks[5].ok"), None);
+        assert_eq!(lookup_json_path(&value, "data.checks[x]"), None);
+        assert_eq!(lookup_json_path(&value, "data.checks.ok"), None);
+    }
+
+    #[test]
+    fn evaluate_status_codes() {
+        let spec = HealthCheckSpec::default();
+        assert!(evaluate(&response(204, ""), &spec).is_ok());
+        assert!(evaluate(&response(301, ""), &spec).is_ok());
+        assert_eq!(failure_of(evaluate(&response(503, ""), &spec)), Some(FailureCategory::BadStatus));
+
+        let spec = HealthCheckSpec {
+            expected_status: vec![401],
+            ..Default::default()
+        };
+        assert!(evaluate(&response(401, ""), &spec).is_ok());
+        assert_eq!(failure_of(evaluate(&response(200, ""), &spec)), Some(FailureCategory::BadStatus));
+    }
+
+    #[test]
+    fn evaluate_body_expectations() {
+        let spec = HealthCheckSpec {
+            body_contains: Some("ready".to_string()),
+            body_regex: Some(r"^v\d+".to_string()),
+            ..Default::default()
+        };
+        assert!(evaluate(&response(200, "v2 ready"), &spec).is_ok());
+        assert_eq!(failure_of(evaluate(&response(200, "v2 busy"), &spec)), Some(FailureCategory::AssertionFailed));
+        assert_eq!(failure_of(evaluate(&response(200, "ready"), &spec)), Some(FailureCategory::AssertionFailed));
+
+        let spec = HealthCheckSpec {
+            body_regex: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(failure_of(evaluate(&response(200, ""), &spec)), Some(FailureCategory::InvalidConfig));
+    }
+
+    #[test]
+    fn evaluate_json_assertions() {
+        let spec = HealthCheckSpec {
+            json_assertions: vec![JsonAssertion {
+                path: "db.ok".to_string(),
+                equals: json!(true),
+            }],
+            ..Default::default()
+        };
+        assert!(evaluate(&response(200, r#"{"db": {"ok": true}}"#), &spec).is_ok());
+        for body in [r#"{"db": {"ok": false}}"#, r#"{"db": {}}"#, "not json"] {
+            assert_eq!(failure_of(evaluate(&response(200, body), &spec)), Some(FailureCategory::AssertionFailed));
+        }
+    }
+
+    #[test]
+    fn judge_response_statuses() {
+        let spec = HealthCheckSpec {
+            degraded_latency_ms: Some(100),
+            ..Default::default()
+        };
+        let result = judge_response(Ok(response(200, "")), 50, &spec);
+        assert_eq!(result.status, HealthStatus::Healthy);
+        assert_eq!(result.http_status, Some(200));
+
+        let result = judge_response(Ok(response(200, "")), 150, &spec);
+        assert_eq!(result.status, HealthStatus::Degraded);
+        assert!(result.is_up());
+
+        let result = judge_response(Ok(response(500, "")), 50, &spec);
+        assert_eq!(result.status, HealthStatus::Unhealthy);
+        assert_eq!(result.http_status, Some(500));
+        assert_eq!(result.failure, Some(FailureCategory::BadStatus));
+
+        let failure = CheckFailure::new(FailureCategory::Timeout, "timed out");
+        let result = judge_response(Err(failure), 2000, &spec);
+        assert_eq!(result.failure, Some(FailureCategory::Timeout));
+        assert_eq!(result.http_status, None);
+    }
+
+    #[test]
+    fn port_targets_default_to_both_loopbacks() {
+        let addrs = PortCheckSpec::default().targets(8080).ok().unwrap();
+        assert_eq!(
+            addrs,
+            vec![
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080),
+                SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080),
+            ]
+        );
+    }
+
+    #[test]
+    fn port_targets_filter_by_family() {
+        let spec = PortCheckSpec {
+            family: AddressFamily::Ipv6,
+            ..Default::default()
+        };
+        let addrs = spec.targets(53).ok().unwrap();
+        assert_eq!(addrs, vec![SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 53)]);
+
+        let spec = PortCheckSpec {
+            host: Some("10.0.0.1".to_string()),
+            family: AddressFamily::Ipv6,
+            ..Default::default()
+        };
+        assert_eq!(spec.targets(53).err().map(|f| f.category), Some(FailureCategory::Dns));
+    }
+
+    #[test]
+    fn port_targets_use_the_host() {
+        let spec = PortCheckSpec {
+            host: Some("::1".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(spec.targets(22).ok().unwrap(), vec!["[::1]:22".parse().unwrap()]);
+    }
+
+    #[test]
+    fn tcp_probe_reports_open_and_closed_ports() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert!(probe_tcp(&addr, Duration::from_millis(500)).is_ok());
+
+        drop(listener);
+        let failure = probe_tcp(&addr, Duration::from_millis(500)).err().unwrap();
+        assert_eq!(failure.category, FailureCategory::ConnectionRefused);
+    }
+
+    #[test]
+    fn udp_probe_reads_a_reply() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let echo = std::thread::spawn(move || {
+            let mut buf = [0u8; 16];
+            let (_, from) = server.recv_from(&mut buf).unwrap();
+            server.send_to(b"pong", from).unwrap();
+        });
+        let message = probe_udp(&addr, Duration::from_secs(2)).ok().unwrap();
+        assert!(message.ends_with("/udp replied"));
+        echo.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn decode_chunked_bodies() {
+        assert_eq!(decode_chunked(b"4\r\nWiki\r\n5;ext=1\r\npedia\r\n0\r\n\r\n"), b"Wikipedia");
+        assert_eq!(decode_chunked(b"A\r\n0123456789\r\n0\r\n\r\n"), b"0123456789");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn decode_chunked_stops_at_malformed_input() {
+        // Truncated chunk
+        assert_eq!(decode_chunked(b"4\r\nWiki\r\n5\r\npe"), b"Wiki");
+        // Size that isn't hex
+        assert_eq!(decode_chunked(b"4\r\nWiki\r\nzz\r\npedia\r\n"), b"Wiki");
+        // No terminating chunk or size line at all
+        assert_eq!(decode_chunked(b"4\r\nWiki"), b"Wiki");
+        assert_eq!(decode_chunked(b"Wiki"), b"");
+        assert_eq!(decode_chunked(b""), b"");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_plain_and_chunked_responses() {
+        let parsed = parse_http_response(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK").ok().unwrap();
+        assert_eq!(parsed.status, 200);
+        assert_eq!(parsed.body, "OK");
+
+        let raw = b"HTTP/1.1 503 Service Unavailable\r\nTransfer-Encoding: chunked\r\n\r\n4\r\ndown\r\n0\r\n\r\n";
+        let parsed = parse_http_response(raw).ok().unwrap();
+        assert_eq!(parsed.status, 503);
+        assert_eq!(parsed.body, "down");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parse_rejects_malformed_responses() {
+        let failure = parse_http_response(b"HTTP/1.1 200 OK\r\nServer: x\r\n").err().unwrap();
+        assert_eq!(failure.message, "Incomplete HTTP response");
+        let failure = parse_http_response(b"garbage\r\n\r\n").err().unwrap();
+        assert_eq!(failure.message, "Malformed HTTP status line");
+        assert!(parse_http_response(b"HTTP/1.1 abc OK\r\n\r\n").is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Idle detection ("自动休眠") for services that sit unused most of the day.
+//!
+//! A background sampler reads each service's CPU usage (the CPU time it used
+//! since the previous sample, not `ps`'s lifetime average) and the number of
+//! established connections on its `port`. Once a service stays below its CPU
+//! threshold with no connections for the configured idle period, its policy
+//! decides whether to just notify or to stop it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{collect_services, load_all_metadata, notify, unix_now, unload_service};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_CPU_THRESHOLD: f32 = 1.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleAction {
+    #[default]
+    Notify,
+    Stop,
+}
+
+/// Per-service idle policy stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IdlePolicy {
+    /// How long the service must stay idle before the action runs
+    pub idle_minutes: u32,
+    /// CPU usage (percent of one core) below which the process counts as idle
+    #[serde(default = "default_cpu_threshold")]
+    pub cpu_threshold: f32,
+    #[serde(default)]
+    pub action: IdleAction,
+}
+
+fn default_cpu_threshold() -> f32 {
+    DEFAULT_CPU_THRESHOLD
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IdleStatus {
+    pub label: String,
+    pub cpu_percent: Option<f32>,
+    pub connections: usize,
+    /// Unix timestamp (seconds) since which the service has been idle
+    pub idle_since: Option<u64>,
+    pub idle_minutes: u32,
+    pub action: IdleAction,
+    /// Whether the policy already fired for the current idle period
+    pub action_taken: bool,
+    #[serde(skip)]
+    cpu_sample: Option<CpuSample>,
+}
+
+/// Cumulative CPU time of a process at one sample
+#[derive(Debug, Clone)]
+struct CpuSample {
+    pid: i32,
+    cpu_secs: f64,
+    at: Instant,
+}
+
+/// Payload of the `service-idle` event
+#[derive(Debug, Serialize, Clone)]
+struct IdleEvent {
+    label: String,
+    idle_minutes: u32,
+    stopped: bool,
+}
+
+/// Managed state shared between the sampler thread and commands
+#[derive(Default)]
+pub struct IdleMonitor {
+    statuses: Mutex<HashMap<String, IdleStatus>>,
+}
+
+/// Parse `ps`'s cumulative CPU time, `[[dd-]hh:]mm:ss[.cc]`, into seconds
+fn parse_cpu_time(text: &str) -> Option<f64> {
+    let (days, clock) = match text.trim().split_once('-') {
+        Some((days, clock)) => (days.parse::<f64>().ok()?, clock),
+        None => (0.0, text.trim()),
+    };
+    let secs = clock
+        .split(':')
+        .try_fold(0.0, |acc, part| part.parse::<f64>().ok().map(|v| acc * 60.0 + v))?;
+    Some(days * 86400.0 + secs)
+}
+
+/// Total CPU time a process has used so far, in seconds
+fn sample_cpu(pid: i32) -> Option<f64> {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        let output = Command::new("ps")
+            .args(["-o", "time=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        parse_cpu_time(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Number of established TCP connections on a local port
+fn count_connections(port: u16) -> usize {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    {
+        Command::new("lsof")
+            .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:ESTABLISHED"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).lines().skip(1).count())
+            .unwrap_or(0)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        Command::new("cmd")
+            .args(["/C", &format!("netstat -an | findstr :{} | findstr ESTABLISHED", port)])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).lines().count())
+            .unwrap_or(0)
+    }
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        sample(&app);
+        std::thread::sleep(SAMPLE_INTERVAL);
+    });
+}
+
+fn sample(app: &AppHandle) {
+    let Ok(services) = collect_services() else { return };
+    let metadata = load_all_metadata();
+    let now = unix_now();
+
+    let mut fired = vec![];
+    {
+        let monitor = app.state::<IdleMonitor>();
+        let Ok(mut statuses) = monitor.statuses.lock() else { return };
+        let mut seen = vec![];
+
+        for service in services.iter().filter(|s| s.is_loaded) {
+            let Some(policy) = metadata.get(&service.label).and_then(|m| m.idle_policy.clone()) else {
+                continue;
+            };
+            let Some(pid) = service.pid else { continue };
+            seen.push(service.label.clone());
+
+            let cpu_secs = sample_cpu(pid);
+            let sampled_at = Instant::now();
+            let connections = service.service_port().map(count_connections).unwrap_or(0);
+
+            let status = statuses.entry(service.label.clone()).or_insert_with(|| IdleStatus {
+                label: service.label.clone(),
+                cpu_percent: None,
+                connections: 0,
+                idle_since: None,
+                idle_minutes: policy.idle_minutes,
+                action: policy.action,
+                action_taken: false,
+                cpu_sample: None,
+            });
+            // Usage over the last interval; unknown until the same process was sampled twice
+            let cpu_percent = match (&status.cpu_sample, cpu_secs) {
+                (Some(previous), Some(secs)) if previous.pid == pid => {
+                    let wall = sampled_at.duration_since(previous.at).as_secs_f64();
+                    (wall > 0.0).then(|| ((secs - previous.cpu_secs).max(0.0) / wall * 100.0) as f32)
+                }
+                _ => None,
+            };
+            status.cpu_sample = cpu_secs.map(|cpu_secs| CpuSample {
+                pid,
+                cpu_secs,
+                at: sampled_at,
+            });
+            let idle_now = connections == 0 && cpu_percent.is_some_and(|cpu| cpu < policy.cpu_threshold);
+            status.cpu_percent = cpu_percent;
+            status.connections = connections;
+            status.idle_minutes = policy.idle_minutes;
+            status.action = policy.action;
+
+            if !idle_now {
+                status.idle_since = None;
+                status.action_taken = false;
+                continue;
+            }
+            let since = *status.idle_since.get_or_insert(now);
+            if !status.action_taken && now.saturating_sub(since) >= u64::from(policy.idle_minutes) * 60 {
+                status.action_taken = true;
+                fired.push((service.clone(), policy));
+            }
+        }
+
+        // Drop services that stopped or lost their policy
+        statuses.retain(|label, _| seen.contains(label));
+    }
+
+    for (service, policy) in fired {
+        let name = service.display_name.clone().unwrap_or_else(|| service.label.clone());
+        let stopped = policy.action == IdleAction::Stop && unload_service(service.file_path.clone()).is_ok();
+        let body = if stopped {
+            format!("{} was idle for {} min and has been stopped", name, policy.idle_minutes)
+        } else {
+            format!("{} has been idle for {} min, consider stopping it", name, policy.idle_minutes)
+        };
+        notify::send_for_service(app, &service.label, "Idle service", &body);
+        let _ = app.emit(
+            "service-idle",
+            IdleEvent {
+                label: service.label,
+                idle_minutes: policy.idle_minutes,
+                stopped,
+            },
+        );
+    }
+}
+
+#[tauri::command]
+pub fn get_idle_status(monitor: tauri::State<'_, IdleMonitor>) -> Result<Vec<IdleStatus>, String> {
+    let statuses = monitor.statuses.lock().map_err(|e| e.to_string())?;
+    let mut list: Vec<IdleStatus> = statuses.values().cloned().collect();
+    list.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpu_time_formats() {
+        // macOS: m:ss.cc
+        assert_eq!(parse_cpu_time("   0:01.50\n"), Some(1.5));
+        assert_eq!(parse_cpu_time("12:03.25"), Some(723.25));
+        // Linux: [dd-]hh:mm:ss
+        assert_eq!(parse_cpu_time("01:02:03"), Some(3723.0));
+        assert_eq!(parse_cpu_time("2-00:00:10"), Some(172810.0));
+        assert_eq!(parse_cpu_time(""), None);
+        assert_eq!(parse_cpu_time("n/a"), None);
+    }
+}
@@ -1,12 +1,31 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::net::TcpStream;
 use std::path::PathBuf;
 use std::process::Command;
 use std::time::Duration;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
+mod activator;
+mod bulk;
+mod crash_loop;
+mod dependencies;
+mod groups;
+mod health;
+mod idle;
+mod log_parser;
+mod logs;
+mod monitor;
+mod notify;
+mod probes;
+mod remediation;
+mod rotation;
+mod scheduler;
+mod startup;
+mod tray;
+mod uptime;
+mod webhooks;
+
 /// Returns the current platform: "macos", "windows", or "linux"
 fn get_current_platform() -> &'static str {
     if cfg!(target_os = "macos") {
@@ -23,6 +42,17 @@ fn get_platform() -> String {
     get_current_platform().to_string()
 }
 
+/// Coarse run state shown for each service
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    #[default]
+    Stopped,
+    Running,
+    /// Loaded, but its process keeps dying and being respawned
+    CrashLooping,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LaunchAgent {
     pub label: String,
@@ -37,15 +67,31 @@ pub struct LaunchAgent {
     pub file_path: String,
     pub is_loaded: bool,
     pub pid: Option<i32>,
+    pub state: ServiceState,
     // Metadata fields
     pub display_name: Option<String>,
     pub description: Option<String>,
     pub icon: Option<String>,
     pub port: Option<u16>,
     pub health_url: Option<String>,
+    pub health_check: Option<health::HealthCheckSpec>,
+    pub port_check: Option<health::PortCheckSpec>,
+    pub command_check: Option<health::CommandCheckSpec>,
+    pub probe: Option<probes::ProbeSpec>,
+    pub socket_check: Option<health::SocketCheckSpec>,
     pub order: Option<i32>,
     pub project_path: Option<String>,
     pub app_path: Option<String>, // 用于标识是否为应用模式
+    pub depends_on: Vec<String>,
+    pub on_demand: Option<activator::OnDemandConfig>,
+}
+
+impl LaunchAgent {
+    /// Port the service process itself listens on. For on-demand services
+    /// ServiceMaster holds `port`, so checks must go to the backend port.
+    pub(crate) fn service_port(&self) -> Option<u16> {
+        self.on_demand.as_ref().map(|c| c.backend_port).or(self.port)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -54,10 +100,32 @@ pub struct ServiceMetadata {
     pub description: Option<String>,
     pub icon: Option<String>,
     pub port: Option<u16>,
+    /// Host, address family and protocol for probing `port`; defaults to TCP on loopback
+    pub port_check: Option<health::PortCheckSpec>,
     pub health_url: Option<String>,
+    /// Expectations for `health_url`; without it any 2xx/3xx counts as healthy
+    pub health_check: Option<health::HealthCheckSpec>,
+    /// Command run in the service's environment, for services without an HTTP endpoint
+    pub command_check: Option<health::CommandCheckSpec>,
+    /// Protocol-level probe (WebSocket, Redis, Postgres, MySQL, TLS expiry)
+    pub probe: Option<probes::ProbeSpec>,
+    /// Unix domain socket for services that don't listen on a TCP port
+    pub socket_check: Option<health::SocketCheckSpec>,
     pub order: Option<i32>,
     pub project_path: Option<String>,
     pub app_path: Option<String>, // 用于标识是否为应用模式
+    /// Labels of services that must be running before this one starts
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    pub crash_loop_policy: Option<crash_loop::CrashLoopPolicy>,
+    pub idle_policy: Option<idle::IdlePolicy>,
+    pub on_demand: Option<activator::OnDemandConfig>,
+    /// How often the background monitor checks this service; defaults to 15s
+    pub check_interval_secs: Option<u64>,
+    /// Restart the service after repeated failed health checks
+    pub remediation: Option<remediation::RemediationPolicy>,
+    /// Rotate and prune StandardOutPath/StandardErrorPath, which launchd never does
+    pub log_rotation: Option<rotation::LogRotationPolicy>,
 }
 
 /// Preset service template
@@ -252,18 +320,33 @@ fn parse_plist_file(path: &PathBuf) -> Option<LaunchAgent> {
         file_path: path.to_string_lossy().to_string(),
         is_loaded: false,
         pid: None,
+        state: ServiceState::Stopped,
         display_name: None,
         description: None,
         icon: None,
         port: None,
         health_url: None,
+        health_check: None,
+        port_check: None,
+        command_check: None,
+        probe: None,
+        socket_check: None,
         order: None,
         project_path: None,
         app_path: None,
+        depends_on: vec![],
+        on_demand: None,
     })
 }
 
-fn get_loaded_services() -> HashMap<String, Option<i32>> {
+/// Run state of a loaded job as reported by the service manager
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct JobState {
+    pub pid: Option<i32>,
+    pub last_exit_status: Option<i32>,
+}
+
+fn get_loaded_services() -> HashMap<String, JobState> {
     let mut services = HashMap::new();
 
     #[cfg(target_os = "macos")]
@@ -279,8 +362,9 @@ fn get_loaded_services() -> HashMap<String, Option<i32>> {
                 let parts: Vec<&str> = line.split_whitespace().collect();
                 if parts.len() >= 3 {
                     let pid = parts[0].parse::<i32>().ok();
+                    let last_exit_status = parts[1].parse::<i32>().ok();
                     let label = parts[2].to_string();
-                    services.insert(label, pid);
+                    services.insert(label, JobState { pid, last_exit_status });
                 }
             }
         }
@@ -307,7 +391,7 @@ fn get_loaded_services() -> HashMap<String, Option<i32>> {
                 let parts: Vec<&str> = line.split_whitespace().collect();
                 if !parts.is_empty() {
                     let label = parts[0].trim_end_matches(".service").to_string();
-                    services.insert(label, None); // systemctl doesn't show PID in list
+                    services.insert(label, JobState::default()); // systemctl doesn't show PID in list
                 }
             }
         }
@@ -317,14 +401,29 @@ fn get_loaded_services() -> HashMap<String, Option<i32>> {
 }
 
 #[tauri::command]
-fn get_services() -> Result<Vec<LaunchAgent>, String> {
+fn get_services(crash_monitor: tauri::State<'_, crash_loop::CrashLoopMonitor>) -> Result<Vec<LaunchAgent>, String> {
+    let mut agents = collect_services()?;
+    for agent in agents.iter_mut().filter(|a| a.is_loaded) {
+        if crash_monitor.is_looping(&agent.label) {
+            agent.state = ServiceState::CrashLooping;
+        }
+    }
+    Ok(agents)
+}
+
+/// Scan the agents directory and merge run state and metadata into each service
+fn collect_services() -> Result<Vec<LaunchAgent>, String> {
+    collect_services_from(&get_loaded_services())
+}
+
+/// Like `collect_services`, with run state from an existing `get_loaded_services` snapshot
+fn collect_services_from(loaded_services: &HashMap<String, JobState>) -> Result<Vec<LaunchAgent>, String> {
     let agents_dir = get_launch_agents_dir();
 
     if !agents_dir.exists() {
         return Ok(vec![]);
     }
 
-    let loaded_services = get_loaded_services();
     let all_metadata = load_all_metadata();
 
     let entries = fs::read_dir(&agents_dir).map_err(|e| e.to_string())?;
@@ -336,9 +435,10 @@ fn get_services() -> Result<Vec<LaunchAgent>, String> {
             let path = entry.path();
             if path.extension().map_or(false, |ext| ext == "plist") {
                 if let Some(mut agent) = parse_plist_file(&path) {
-                    if let Some(pid) = loaded_services.get(&agent.label) {
+                    if let Some(state) = loaded_services.get(&agent.label) {
                         agent.is_loaded = true;
-                        agent.pid = *pid;
+                        agent.pid = state.pid;
+                        agent.state = ServiceState::Running;
                     }
                     // Apply metadata
                     if let Some(meta) = all_metadata.get(&agent.label) {
@@ -347,9 +447,16 @@ fn get_services() -> Result<Vec<LaunchAgent>, String> {
                         agent.icon = meta.icon.clone();
                         agent.port = meta.port;
                         agent.health_url = meta.health_url.clone();
+                        agent.health_check = meta.health_check.clone();
+                        agent.port_check = meta.port_check.clone();
+                        agent.command_check = meta.command_check.clone();
+                        agent.probe = meta.probe.clone();
+                        agent.socket_check = meta.socket_check.clone();
                         agent.order = meta.order;
                         agent.project_path = meta.project_path.clone();
                         agent.app_path = meta.app_path.clone();
+                        agent.depends_on = meta.depends_on.clone();
+                        agent.on_demand = meta.on_demand.clone();
                     }
                     agents.push(agent);
                 }
@@ -367,12 +474,71 @@ fn get_services() -> Result<Vec<LaunchAgent>, String> {
     Ok(agents)
 }
 
-#[tauri::command]
+/// Find a single service by its label
+fn find_service(label: &str) -> Result<LaunchAgent, String> {
+    collect_services()?
+        .into_iter()
+        .find(|agent| agent.label == label)
+        .ok_or_else(|| format!("Service '{}' not found", label))
+}
+
+/// Whether a running service answers on its configured port and health URL.
+/// Services without either are considered healthy as soon as they are loaded.
+fn is_service_healthy(agent: &LaunchAgent) -> bool {
+    health::check_service(agent).summary().is_none_or(|r| r.is_up())
+}
+
+/// Poll a service until it reports healthy or the timeout elapses
+fn wait_until_healthy(agent: &LaunchAgent, timeout: Duration) -> Result<(), String> {
+    let start = std::time::Instant::now();
+    loop {
+        if is_service_healthy(agent) {
+            return Ok(());
+        }
+        if start.elapsed() >= timeout {
+            return Err(format!(
+                "Service '{}' did not become healthy within {}s",
+                agent.label,
+                timeout.as_secs()
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+/// Start a service after its dependencies are up.
+/// Runs on the async pool since waiting on dependency health checks can take a while.
+#[tauri::command(async)]
 fn load_service(plist_path: String) -> Result<String, String> {
+    let agent = parse_plist_file(&PathBuf::from(&plist_path))
+        .ok_or_else(|| "Invalid service file".to_string())?;
+    dependencies::start_dependencies(&agent.label)?;
+    launchctl_load(&plist_path)
+}
+
+/// Stop a service after stopping everything that depends on it
+#[tauri::command(async)]
+fn unload_service(plist_path: String) -> Result<String, String> {
+    let agent = parse_plist_file(&PathBuf::from(&plist_path))
+        .ok_or_else(|| "Invalid service file".to_string())?;
+    dependencies::stop_dependents(&agent.label)?;
+    launchctl_unload(&plist_path)
+}
+
+#[tauri::command(async)]
+fn restart_service(plist_path: String) -> Result<String, String> {
+    let agent = parse_plist_file(&PathBuf::from(&plist_path))
+        .ok_or_else(|| "Invalid service file".to_string())?;
+    dependencies::start_dependencies(&agent.label)?;
+    launchctl_restart(&plist_path)
+}
+
+fn launchctl_load(plist_path: &str) -> Result<String, String> {
+    crash_loop::expect_restart(plist_path);
     #[cfg(target_os = "macos")]
     {
         let output = Command::new("launchctl")
-            .args(["load", &plist_path])
+            .args(["load", plist_path])
             .output()
             .map_err(|e| e.to_string())?;
 
@@ -391,7 +557,7 @@ fn load_service(plist_path: String) -> Result<String, String> {
 
     #[cfg(target_os = "linux")]
     {
-        let service_name = std::path::Path::new(&plist_path)
+        let service_name = std::path::Path::new(plist_path)
             .file_stem()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default();
@@ -409,12 +575,11 @@ fn load_service(plist_path: String) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-fn unload_service(plist_path: String) -> Result<String, String> {
+fn launchctl_unload(plist_path: &str) -> Result<String, String> {
     #[cfg(target_os = "macos")]
     {
         let output = Command::new("launchctl")
-            .args(["unload", &plist_path])
+            .args(["unload", plist_path])
             .output()
             .map_err(|e| e.to_string())?;
 
@@ -432,7 +597,7 @@ fn unload_service(plist_path: String) -> Result<String, String> {
 
     #[cfg(target_os = "linux")]
     {
-        let service_name = std::path::Path::new(&plist_path)
+        let service_name = std::path::Path::new(plist_path)
             .file_stem()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default();
@@ -450,18 +615,18 @@ fn unload_service(plist_path: String) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-fn restart_service(plist_path: String) -> Result<String, String> {
+fn launchctl_restart(plist_path: &str) -> Result<String, String> {
+    crash_loop::expect_restart(plist_path);
     #[cfg(target_os = "macos")]
     {
         // First unload
         let _ = Command::new("launchctl")
-            .args(["unload", &plist_path])
+            .args(["unload", plist_path])
             .output();
 
         // Then load
         let output = Command::new("launchctl")
-            .args(["load", &plist_path])
+            .args(["load", plist_path])
             .output()
             .map_err(|e| e.to_string())?;
 
@@ -479,7 +644,7 @@ fn restart_service(plist_path: String) -> Result<String, String> {
 
     #[cfg(target_os = "linux")]
     {
-        let service_name = std::path::Path::new(&plist_path)
+        let service_name = std::path::Path::new(plist_path)
             .file_stem()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_default();
@@ -497,22 +662,10 @@ fn restart_service(plist_path: String) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-fn get_service_logs(log_path: String, lines: usize) -> Result<Vec<String>, String> {
-    let content = fs::read_to_string(&log_path).map_err(|e| e.to_string())?;
-    let all_lines: Vec<&str> = content.lines().collect();
-    let start = if all_lines.len() > lines {
-        all_lines.len() - lines
-    } else {
-        0
-    };
-    Ok(all_lines[start..].iter().map(|s| s.to_string()).collect())
-}
-
-#[tauri::command]
-fn clear_service_logs(log_path: String) -> Result<String, String> {
-    fs::write(&log_path, "").map_err(|e| e.to_string())?;
-    Ok("Logs cleared successfully".to_string())
+/// ServiceMaster itself listens on the ports of on-demand services; never report
+/// (and offer to kill) our own process as a port conflict
+fn is_own_pid(pid: i32) -> bool {
+    u32::try_from(pid).is_ok_and(|pid| pid == std::process::id())
 }
 
 #[tauri::command]
@@ -525,12 +678,10 @@ fn get_process_by_port(port: u16) -> Result<Option<i32>, String> {
             .map_err(|e| e.to_string())?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        if let Some(line) = stdout.lines().next() {
-            if let Ok(pid) = line.trim().parse::<i32>() {
-                return Ok(Some(pid));
-            }
-        }
-        Ok(None)
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.trim().parse::<i32>().ok())
+            .find(|pid| !is_own_pid(*pid)))
     }
 
     #[cfg(target_os = "windows")]
@@ -542,15 +693,10 @@ fn get_process_by_port(port: u16) -> Result<Option<i32>, String> {
             .map_err(|e| e.to_string())?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        if let Some(line) = stdout.lines().next() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if let Some(pid_str) = parts.last() {
-                if let Ok(pid) = pid_str.trim().parse::<i32>() {
-                    return Ok(Some(pid));
-                }
-            }
-        }
-        Ok(None)
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.split_whitespace().last()?.trim().parse::<i32>().ok())
+            .find(|pid| !is_own_pid(*pid)))
     }
 
     #[cfg(target_os = "linux")]
@@ -561,12 +707,10 @@ fn get_process_by_port(port: u16) -> Result<Option<i32>, String> {
             .map_err(|e| e.to_string())?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        if let Some(line) = stdout.lines().next() {
-            if let Ok(pid) = line.trim().parse::<i32>() {
-                return Ok(Some(pid));
-            }
-        }
-        Ok(None)
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.trim().parse::<i32>().ok())
+            .find(|pid| !is_own_pid(*pid)))
     }
 }
 
@@ -601,59 +745,6 @@ fn kill_process(pid: i32) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-fn check_port(port: u16) -> Result<bool, String> {
-    let addr = format!("127.0.0.1:{}", port);
-    match TcpStream::connect_timeout(
-        &addr.parse().map_err(|e: std::net::AddrParseError| e.to_string())?,
-        Duration::from_millis(500),
-    ) {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
-    }
-}
-
-#[tauri::command]
-fn check_health(url: String) -> Result<bool, String> {
-    // Try using reqwest first (cross-platform)
-    #[cfg(feature = "native-tls")]
-    {
-        match reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(2))
-            .build()
-        {
-            Ok(client) => {
-                match client.get(&url).send() {
-                    Ok(response) => {
-                        let status = response.status().as_u16();
-                        return Ok((200..400).contains(&status));
-                    }
-                    Err(_) => return Ok(false),
-                }
-            }
-            Err(_) => {}
-        }
-    }
-
-    // Fallback to curl on Unix systems
-    #[cfg(any(target_os = "macos", target_os = "linux"))]
-    {
-        let output = Command::new("curl")
-            .args(["-s", "-o", "/dev/null", "-w", "%{http_code}", "-m", "2", &url])
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        let status_code = String::from_utf8_lossy(&output.stdout);
-        return Ok(status_code.starts_with('2') || status_code.starts_with('3'));
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        // Fallback for Windows without reqwest
-        Ok(false)
-    }
-}
-
 #[tauri::command]
 fn open_url(url: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
@@ -820,10 +911,12 @@ fn quit_app(app_name: String) -> Result<String, String> {
 }
 
 #[tauri::command]
-fn save_service_metadata(label: String, metadata: ServiceMetadata) -> Result<String, String> {
+fn save_service_metadata(app: tauri::AppHandle, label: String, metadata: ServiceMetadata) -> Result<String, String> {
     let mut all_metadata = load_all_metadata();
     all_metadata.insert(label, metadata);
+    dependencies::validate(&all_metadata)?;
     save_all_metadata(&all_metadata)?;
+    activator::sync_listeners(&app);
     Ok("Metadata saved successfully".to_string())
 }
 
@@ -839,12 +932,14 @@ fn get_all_metadata_map() -> Result<HashMap<String, ServiceMetadata>, String> {
 }
 
 #[tauri::command]
-fn import_metadata(metadata: HashMap<String, ServiceMetadata>) -> Result<String, String> {
+fn import_metadata(app: tauri::AppHandle, metadata: HashMap<String, ServiceMetadata>) -> Result<String, String> {
     let mut current = load_all_metadata();
     for (k, v) in metadata {
         current.insert(k, v);
     }
+    dependencies::validate(&current)?;
     save_all_metadata(&current)?;
+    activator::sync_listeners(&app);
     Ok("Metadata imported successfully".to_string())
 }
 
@@ -872,6 +967,10 @@ pub struct AppSettings {
     pub webdav_username: Option<String>,
     pub webdav_password: Option<String>,
     pub auto_launch: Option<bool>, // 新增：开机自启动
+    #[serde(default)]
+    pub notifications: notify::NotificationSettings,
+    #[serde(default)]
+    pub webhooks: Vec<webhooks::WebhookTarget>,
 }
 
 impl Default for AppSettings {
@@ -885,6 +984,8 @@ impl Default for AppSettings {
             webdav_username: None,
             webdav_password: None,
             auto_launch: Some(false),
+            notifications: notify::NotificationSettings::default(),
+            webhooks: vec![],
         }
     }
 }
@@ -1403,6 +1504,15 @@ fn apply_sync_data(content: &str) -> Result<String, String> {
     Ok(format!("同步成功！(同步时间: {})", sync_data.sync_time))
 }
 
+/// Current Unix time in seconds
+pub(crate) fn unix_now() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Get current time as ISO string (simple implementation without chrono crate)
 fn chrono_now() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -1421,47 +1531,24 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())  // Tauri v2 方式: 注册 updater 插件
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(crash_loop::CrashLoopMonitor::default())
+        .manage(idle::IdleMonitor::default())
+        .manage(activator::OnDemandManager::default())
+        .manage(monitor::HealthMonitor::default())
+        .manage(uptime::UptimeRecorder::default())
+        .manage(notify::Notifier::default())
+        .manage(remediation::Remediator::default())
+        .manage(logs::LogFollowers::default())
         .setup(|app| {
-            use tauri::Manager;
-            use tauri::menu::{Menu, MenuItem};
-            use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-
-            let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let show = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show, &quit])?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .menu(&menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                })
-                .build(app)?;
-
+            tray::create(app.handle())?;
+            crash_loop::spawn(app.handle().clone());
+            scheduler::spawn(app.handle().clone());
+            idle::spawn(app.handle().clone());
+            monitor::spawn(app.handle().clone());
+            rotation::spawn(app.handle().clone());
+            activator::sync_listeners(app.handle());
+            activator::spawn_reaper(app.handle().clone());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1469,12 +1556,21 @@ pub fn run() {
             load_service,
             unload_service,
             restart_service,
-            get_service_logs,
-            clear_service_logs,
+            logs::get_service_logs,
+            logs::read_service_logs,
+            logs::read_merged_service_logs,
+            logs::clear_service_logs,
+            log_parser::get_structured_logs,
+            logs::follow_service_logs,
+            logs::unfollow_service_logs,
+            logs::search_service_logs,
             get_process_by_port,
             kill_process,
-            check_port,
-            check_health,
+            health::check_port,
+            health::check_health,
+            health::check_command,
+            health::check_socket,
+            probes::check_probe,
             open_url,
             check_app_running,
             quit_app,
@@ -1493,7 +1589,35 @@ pub fn run() {
             test_webdav_connection,
             sync_to_webdav,
             sync_from_webdav,
-            get_presets
+            get_presets,
+            groups::get_groups,
+            groups::save_group,
+            groups::delete_group,
+            groups::start_group,
+            groups::stop_group,
+            groups::restart_group,
+            groups::get_group_status,
+            bulk::bulk_load_services,
+            bulk::bulk_unload_services,
+            bulk::bulk_restart_services,
+            bulk::bulk_delete_services,
+            startup::start_service_and_wait,
+            startup::get_start_history,
+            crash_loop::get_crash_loop_status,
+            scheduler::get_schedules,
+            scheduler::save_schedule,
+            scheduler::delete_schedule,
+            scheduler::set_schedule_enabled,
+            idle::get_idle_status,
+            activator::get_on_demand_status,
+            activator::refresh_on_demand_listeners,
+            monitor::get_health_status,
+            uptime::get_service_uptime,
+            webhooks::get_webhook_deliveries,
+            webhooks::test_webhook,
+            remediation::get_remediation_log,
+            rotation::rotate_service_logs,
+            rotation::get_log_disk_usage
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,538 @@
+//! Structured view of log lines.
+//!
+//! Each line is turned into a `LogRecord`: ANSI color codes become styled
+//! segments, JSON lines (pino, bunyan, structlog, ...) keep their fields, and the
+//! level and timestamp are taken from JSON fields or detected in the text
+//! (`ERROR`, `[warn]`, `level=info`, Python logging's `WARNING:root:`). Lines
+//! without a level or timestamp of their own, such as stack traces, inherit the
+//! level of the line before them.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::LazyLock;
+
+use crate::health::{lookup_json_path, JsonAssertion};
+use crate::logs::{detect_timestamp, read_tail};
+
+/// Only the start of a text line is searched for a level
+const LEVEL_SCAN_CHARS: usize = 160;
+/// Bytes scanned backwards for one filtered page
+const FILTER_SCAN_LIMIT: u64 = 32 * 1024 * 1024;
+/// Lines read per step while looking for enough matching records
+const MIN_PAGE_LINES: usize = 200;
+
+static LOGFMT_LEVEL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\b(?:level|lvl|severity)[=:]\s*"?([a-z]+)"#).unwrap());
+static UPPER_LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|ERR|CRIT|CRITICAL|SEVERE|FATAL|PANIC)\b").unwrap()
+});
+static BRACKETED_LEVEL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)[\[<(](trace|debug|info|notice|warn|warning|error|err|critical|fatal)[\]>)]").unwrap());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "trace" | "verbose" => Self::Trace,
+            "debug" | "dbg" => Self::Debug,
+            "info" | "information" | "notice" => Self::Info,
+            "warn" | "warning" => Self::Warn,
+            "error" | "err" => Self::Error,
+            "fatal" | "critical" | "crit" | "severe" | "panic" | "emergency" | "alert" => Self::Fatal,
+            _ => return None,
+        })
+    }
+
+    /// pino / bunyan numeric levels
+    fn from_number(level: u64) -> Option<Self> {
+        Some(match level {
+            0..=10 => Self::Trace,
+            11..=20 => Self::Debug,
+            21..=30 => Self::Info,
+            31..=40 => Self::Warn,
+            41..=50 => Self::Error,
+            51..=60 => Self::Fatal,
+            _ => return None,
+        })
+    }
+}
+
+/// A run of text with the ANSI style it was printed with
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct StyledSegment {
+    pub text: String,
+    /// Color name ("red", "bright_blue") or "#rrggbb" for 256-color and truecolor codes
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl StyledSegment {
+    fn same_style(&self, other: &StyledSegment) -> bool {
+        (&self.fg, &self.bg, self.bold, self.dim, self.italic, self.underline)
+            == (&other.fg, &other.bg, other.bold, other.dim, other.italic, other.underline)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogRecord {
+    /// The line with ANSI escapes removed
+    pub text: String,
+    /// The `msg`/`message` field of JSON lines, otherwise `text`
+    pub message: String,
+    /// Unix milliseconds
+    pub timestamp: Option<i64>,
+    pub level: Option<LogLevel>,
+    /// Fields of a JSON line
+    pub fields: Option<Map<String, Value>>,
+    /// Styled runs of `text`; empty when the line has no color codes
+    pub segments: Vec<StyledSegment>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Keep records at this level or above
+    pub min_level: Option<LogLevel>,
+    /// Keep only these levels; empty keeps all
+    pub levels: Vec<LogLevel>,
+    /// JSON fields that must have the given values, e.g. `{"path": "req.method", "equals": "POST"}`
+    pub fields: Vec<JsonAssertion>,
+}
+
+impl LogFilter {
+    fn matches(&self, record: &LogRecord) -> bool {
+        if self.min_level.is_some_and(|min| record.level.is_none_or(|l| l < min)) {
+            return false;
+        }
+        if !self.levels.is_empty() && record.level.is_none_or(|l| !self.levels.contains(&l)) {
+            return false;
+        }
+        if self.fields.is_empty() {
+            return true;
+        }
+        let Some(fields) = &record.fields else { return false };
+        let object = Value::Object(fields.clone());
+        self.fields
+            .iter()
+            .all(|f| lookup_json_path(&object, &f.path).is_some_and(|v| *v == f.equals))
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StructuredLogPage {
+    pub records: Vec<LogRecord>,
+    /// Byte offset of the oldest record returned (or of the oldest line scanned when
+    /// nothing scanned was left out); pass as `before` to continue
+    pub start_offset: u64,
+    pub has_more: bool,
+}
+
+const BASIC_COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+fn basic_color(index: u16, bright: bool) -> Option<String> {
+    let name = BASIC_COLORS.get(index as usize)?;
+    Some(if bright { format!("bright_{}", name) } else { name.to_string() })
+}
+
+/// Color from the 256-color palette
+fn palette_color(index: u16) -> Option<String> {
+    match index {
+        0..=7 => basic_color(index, false),
+        8..=15 => basic_color(index - 8, true),
+        16..=231 => {
+            let steps = [0, 95, 135, 175, 215, 255];
+            let i = (index - 16) as usize;
+            Some(format!("#{:02x}{:02x}{:02x}", steps[i / 36], steps[(i / 6) % 6], steps[i % 6]))
+        }
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            Some(format!("#{:02x}{:02x}{:02x}", level, level, level))
+        }
+        _ => None,
+    }
+}
+
+/// Apply one SGR sequence (the parameters of `ESC [ ... m`) to a style
+fn apply_sgr(style: &mut StyledSegment, params: &str) {
+    let codes: Vec<u16> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => *style = StyledSegment::default(),
+            1 => style.bold = true,
+            2 => style.dim = true,
+            3 => style.italic = true,
+            4 => style.underline = true,
+            22 => (style.bold, style.dim) = (false, false),
+            23 => style.italic = false,
+            24 => style.underline = false,
+            code @ 30..=37 => style.fg = basic_color(code - 30, false),
+            code @ 40..=47 => style.bg = basic_color(code - 40, false),
+            code @ 90..=97 => style.fg = basic_color(code - 90, true),
+            code @ 100..=107 => style.bg = basic_color(code - 100, true),
+            39 => style.fg = None,
+            49 => style.bg = None,
+            code @ (38 | 48) => {
+                let color = match codes.get(i + 1) {
+                    Some(5) => {
+                        i += 2;
+                        codes.get(i).and_then(|c| palette_color(*c))
+                    }
+                    Some(2) if i + 4 < codes.len() => {
+                        i += 4;
+                        Some(format!("#{:02x}{:02x}{:02x}", codes[i - 2], codes[i - 1], codes[i]))
+                    }
+                    _ => None,
+                };
+                if code == 38 {
+                    style.fg = color;
+                } else {
+                    style.bg = color;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Strip escape sequences, returning the plain text and its styled segments
+/// (empty when the line had no color codes)
+pub(crate) fn parse_ansi(line: &str) -> (String, Vec<StyledSegment>) {
+    if !line.contains('\x1b') {
+        return (line.to_string(), vec![]);
+    }
+    let mut plain = String::with_capacity(line.len());
+    let mut segments: Vec<StyledSegment> = vec![];
+    let mut style = StyledSegment::default();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            plain.push(c);
+            match segments.last_mut() {
+                Some(last) if last.same_style(&style) => last.text.push(c),
+                _ => segments.push(StyledSegment {
+                    text: c.to_string(),
+                    ..style.clone()
+                }),
+            }
+            continue;
+        }
+        match chars.peek() {
+            // CSI: parameters up to a final byte; only SGR ('m') matters
+            Some('[') => {
+                chars.next();
+                let mut params = String::new();
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        if c == 'm' {
+                            apply_sgr(&mut style, &params);
+                        }
+                        break;
+                    }
+                    params.push(c);
+                }
+            }
+            // OSC (e.g. hyperlinks): up to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                chars.next();
+            }
+        }
+    }
+    (plain, segments)
+}
+
+fn field<'a>(fields: &'a Map<String, Value>, names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|name| fields.get(*name))
+}
+
+fn json_level(fields: &Map<String, Value>) -> Option<LogLevel> {
+    match field(fields, &["level", "lvl", "severity", "levelname", "log.level"])? {
+        Value::String(name) => LogLevel::from_name(name),
+        Value::Number(n) => n.as_u64().and_then(LogLevel::from_number),
+        _ => None,
+    }
+}
+
+fn json_timestamp(fields: &Map<String, Value>) -> Option<i64> {
+    match field(fields, &["time", "timestamp", "ts", "@timestamp", "t"])? {
+        Value::String(text) => detect_timestamp(text),
+        // Epoch seconds or milliseconds
+        Value::Number(n) => {
+            let value = n.as_f64()?;
+            Some(if value > 1e11 { value as i64 } else { (value * 1000.0) as i64 })
+        }
+        _ => None,
+    }
+}
+
+fn text_level(text: &str) -> Option<LogLevel> {
+    let head = match text.char_indices().nth(LEVEL_SCAN_CHARS) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    };
+    [&LOGFMT_LEVEL, &UPPER_LEVEL, &BRACKETED_LEVEL]
+        .into_iter()
+        .find_map(|re| re.captures(head).and_then(|caps| LogLevel::from_name(&caps[1])))
+}
+
+/// A text line with no level or timestamp of its own, such as a stack trace frame
+fn is_continuation(record: &LogRecord) -> bool {
+    record.fields.is_none()
+        && record.timestamp.is_none()
+        && !record.text.trim().is_empty()
+        && text_level(&record.text).is_none()
+}
+
+/// Parse one line; `previous` supplies the level for continuation lines
+pub(crate) fn parse_line(line: &str, previous: Option<&LogRecord>) -> LogRecord {
+    let (text, segments) = parse_ansi(line);
+    let fields = text
+        .trim_start()
+        .starts_with('{')
+        .then(|| serde_json::from_str::<Map<String, Value>>(text.trim()).ok())
+        .flatten();
+
+    let (message, timestamp, level) = match &fields {
+        Some(fields) => {
+            let message = match field(fields, &["msg", "message", "@message"]) {
+                Some(Value::String(message)) => message.clone(),
+                _ => text.clone(),
+            };
+            (message, json_timestamp(fields), json_level(fields))
+        }
+        None => (text.clone(), detect_timestamp(&text), text_level(&text)),
+    };
+
+    let mut record = LogRecord {
+        text,
+        message,
+        timestamp,
+        level,
+        fields,
+        segments,
+    };
+    if is_continuation(&record) {
+        record.level = previous.and_then(|p| p.level);
+    }
+    record
+}
+
+pub(crate) fn parse_lines(lines: Vec<String>) -> Vec<LogRecord> {
+    let mut records: Vec<LogRecord> = Vec::with_capacity(lines.len());
+    for line in lines {
+        let record = parse_line(&line, records.last());
+        records.push(record);
+    }
+    records
+}
+
+/// The last `lines` records of a log, parsed, optionally filtered by level and JSON
+/// fields. With a filter, older content is scanned until enough records match.
+#[tauri::command(async)]
+pub fn get_structured_logs(
+    log_path: String,
+    lines: usize,
+    before: Option<u64>,
+    filter: Option<LogFilter>,
+) -> Result<StructuredLogPage, String> {
+    let Some(filter) = filter else {
+        let page = read_tail(&log_path, lines, before, None)?;
+        return Ok(StructuredLogPage {
+            records: parse_lines(page.lines),
+            start_offset: page.start_offset,
+            has_more: page.has_more,
+        });
+    };
+
+    // Matching records with the offset of their line, oldest first
+    let mut records: Vec<(u64, LogRecord)> = vec![];
+    // Leading continuation lines of the newer pages, waiting for the line before them
+    let mut continuing: Vec<(u64, LogRecord)> = vec![];
+    let mut cursor = before;
+    let mut scanned = 0;
+    loop {
+        let page = read_tail(&log_path, lines.max(MIN_PAGE_LINES), cursor, None)?;
+        scanned += page.end_offset - page.start_offset;
+        let done = !page.has_more || scanned >= FILTER_SCAN_LIMIT;
+
+        let mut parsed: Vec<(u64, LogRecord)> = page.offsets.into_iter().zip(parse_lines(page.lines)).collect();
+        let run = parsed.iter().take_while(|(_, r)| is_continuation(r)).count();
+        if run == parsed.len() {
+            // The whole page continues a line further back
+            parsed.append(&mut continuing);
+            continuing = parsed;
+            parsed = vec![];
+        } else {
+            let level = parsed.last().and_then(|(_, r)| r.level);
+            for (_, record) in continuing.iter_mut() {
+                record.level = level;
+            }
+            let mut newer = std::mem::replace(&mut continuing, parsed.drain(..run).collect());
+            parsed.append(&mut newer);
+        }
+        if done {
+            // Nothing further back to inherit from
+            continuing.append(&mut parsed);
+            parsed = std::mem::take(&mut continuing);
+        }
+
+        let mut matched: Vec<(u64, LogRecord)> = parsed.into_iter().filter(|(_, r)| filter.matches(r)).collect();
+        matched.append(&mut records);
+        records = matched;
+
+        cursor = Some(page.start_offset);
+        if done || records.len() >= lines {
+            let truncated = records.len() > lines;
+            if truncated {
+                records.drain(..records.len() - lines);
+            }
+            // Continue from the oldest record returned unless everything scanned was returned
+            let start_offset = match records.first() {
+                Some((offset, _)) if truncated || !done => *offset,
+                _ => page.start_offset,
+            };
+            return Ok(StructuredLogPage {
+                records: records.into_iter().map(|(_, r)| r).collect(),
+                start_offset,
+                has_more: start_offset > 0 && (start_offset != page.start_offset || page.has_more),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn style(params: &str) -> StyledSegment {
+        let mut style = StyledSegment::default();
+        apply_sgr(&mut style, params);
+        style
+    }
+
+    #[test]
+    fn apply_sgr_basic_and_bright_colors() {
+        let s = style("1;31;42");
+        assert!(s.bold);
+        assert_eq!(s.fg.as_deref(), Some("red"));
+        assert_eq!(s.bg.as_deref(), Some("green"));
+        assert_eq!(style("94").fg.as_deref(), Some("bright_blue"));
+        assert_eq!(style("1;31;0").fg, None);
+        assert!(!style("1;22").bold);
+    }
+
+    #[test]
+    fn apply_sgr_256_and_truecolor() {
+        assert_eq!(style("38;5;1").fg.as_deref(), Some("red"));
+        assert_eq!(style("38;5;9").fg.as_deref(), Some("bright_red"));
+        assert_eq!(style("38;5;196").fg.as_deref(), Some("#ff0000"));
+        assert_eq!(style("48;5;232").bg.as_deref(), Some("#080808"));
+        assert_eq!(style("38;2;18;52;86").fg.as_deref(), Some("#123456"));
+        // Codes after an extended color still apply
+        let s = style("38;2;18;52;86;1");
+        assert_eq!(s.fg.as_deref(), Some("#123456"));
+        assert!(s.bold);
+        assert_eq!(style("38;2;1").fg, None);
+    }
+
+    #[test]
+    fn parse_ansi_splits_styled_segments() {
+        let (plain, segments) = parse_ansi("\x1b[31mERROR\x1b[0m done");
+        assert_eq!(plain, "ERROR done");
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "ERROR");
+        assert_eq!(segments[0].fg.as_deref(), Some("red"));
+        assert_eq!(segments[1].text, " done");
+        assert_eq!(segments[1].fg, None);
+
+        let (plain, segments) = parse_ansi("plain line");
+        assert_eq!(plain, "plain line");
+        assert!(segments.is_empty());
+
+        let (plain, _) = parse_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07 \x1b[2Kend");
+        assert_eq!(plain, "link end");
+    }
+
+    #[test]
+    fn text_level_formats() {
+        assert_eq!(text_level("2024-05-01 12:00:00 ERROR failed"), Some(LogLevel::Error));
+        assert_eq!(text_level("[warn] disk almost full"), Some(LogLevel::Warn));
+        assert_eq!(text_level("ts=1 level=debug msg=hi"), Some(LogLevel::Debug));
+        assert_eq!(text_level("WARNING:root:deprecated"), Some(LogLevel::Warn));
+        assert_eq!(text_level("<critical> out of memory"), Some(LogLevel::Fatal));
+        assert_eq!(text_level("an error occurred"), None);
+        assert_eq!(text_level("    at main (index.js:1:1)"), None);
+    }
+
+    #[test]
+    fn json_level_names_and_numbers() {
+        let fields = |json: &str| serde_json::from_str::<Map<String, Value>>(json).unwrap();
+        assert_eq!(json_level(&fields(r#"{"level":"WARNING"}"#)), Some(LogLevel::Warn));
+        assert_eq!(json_level(&fields(r#"{"levelname":"error"}"#)), Some(LogLevel::Error));
+        assert_eq!(json_level(&fields(r#"{"level":30}"#)), Some(LogLevel::Info));
+        assert_eq!(json_level(&fields(r#"{"level":60}"#)), Some(LogLevel::Fatal));
+        assert_eq!(json_level(&fields(r#"{"level":"loud"}"#)), None);
+        assert_eq!(json_level(&fields(r#"{"msg":"hi"}"#)), None);
+    }
+
+    #[test]
+    fn filtered_pages_inherit_levels_across_steps() {
+        let path = std::env::temp_dir().join(format!("log_parser_test_{}.log", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, "INFO starting").unwrap();
+        writeln!(file, "ERROR boom").unwrap();
+        // Longer than one step, so the frames are read before the line they belong to
+        for i in 0..MIN_PAGE_LINES + 50 {
+            writeln!(file, "    at frame {}", i).unwrap();
+        }
+        drop(file);
+        let log_path = path.to_string_lossy().into_owned();
+        let errors = || {
+            Some(LogFilter {
+                min_level: Some(LogLevel::Error),
+                ..Default::default()
+            })
+        };
+
+        let all = get_structured_logs(log_path.clone(), 1000, None, errors()).unwrap();
+        assert_eq!(all.records.len(), MIN_PAGE_LINES + 51);
+        assert_eq!(all.records[0].text, "ERROR boom");
+        assert_eq!(all.start_offset, 0);
+        assert!(!all.has_more);
+
+        let page = get_structured_logs(log_path.clone(), 5, None, errors()).unwrap();
+        assert_eq!(page.records.len(), 5);
+        assert!(page.records.iter().all(|r| r.level == Some(LogLevel::Error)));
+        assert!(page.has_more);
+        let older = get_structured_logs(log_path.clone(), 1, Some(page.start_offset), errors()).unwrap();
+        let expected = format!("    at frame {}", MIN_PAGE_LINES + 44);
+        assert_eq!(older.records[0].text, expected);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,931 @@
+//! Reading service log files.
+//!
+//! Logs can grow to gigabytes, so nothing here reads a whole file. Tails are read
+//! backwards from the end in fixed-size chunks until enough lines (or the byte
+//! cap) are collected, bytes are decoded lossily, and every page carries the byte
+//! offset of its first line so the UI can keep paging backwards.
+//!
+//! Following polls a service's stdout/stderr files and emits new lines as
+//! `service-log-lines` events. A file that shrinks was truncated and is re-read
+//! from the start; a path that points at a different file (or disappears) was
+//! rotated, so the old handle is drained before switching to the new file.
+//!
+//! The merged view interleaves stdout and stderr by the timestamps detected on
+//! their lines; a line without one stays right after the line before it. A stream
+//! without any timestamps can't be ordered after the fact, so it follows the rest.
+//! Live following numbers its events instead, so the UI can keep read order.
+//!
+//! Searching scans stdout, stderr and their rotated (possibly gzipped) siblings
+//! line by line. Lines without a timestamp (stack traces, wrapped output) inherit
+//! the timestamp of the line before them when a time range is given.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone};
+use flate2::read::MultiGzDecoder;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{find_service, LaunchAgent};
+
+const CHUNK_SIZE: u64 = 64 * 1024;
+/// Upper bound on bytes scanned for one page
+const DEFAULT_MAX_BYTES: u64 = 4 * 1024 * 1024;
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+/// Bytes read per file per poll, so a burst doesn't produce one giant event
+const FOLLOW_READ_LIMIT: u64 = 1024 * 1024;
+const DEFAULT_MAX_MATCHES: usize = 500;
+/// Only the start of a line is searched for a timestamp
+const TIMESTAMP_SCAN_CHARS: usize = 64;
+
+/// ISO 8601 / RFC 3339 and Python logging style ("2024-05-01 12:00:00,123")
+static ISO_TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{4}-\d{2}-\d{2})[T ](\d{2}:\d{2}:\d{2})(?:[.,](\d{1,9}))?\s?(Z|[+-]\d{2}:?\d{2})?").unwrap()
+});
+/// Sequence number of the next `service-log-lines` event
+static NEXT_EVENT_SEQ: AtomicU64 = AtomicU64::new(1);
+/// Syslog style ("May  1 12:00:00"), which has no year
+static SYSLOG_TIMESTAMP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b([A-Z][a-z]{2}) +(\d{1,2}) (\d{2}:\d{2}:\d{2})\b").unwrap());
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogPage {
+    pub lines: Vec<String>,
+    /// Byte offset where each line starts
+    pub offsets: Vec<u64>,
+    /// Byte offset of the first returned line; pass as `before` to get the previous page
+    pub start_offset: u64,
+    /// Byte offset just past the last returned line
+    pub end_offset: u64,
+    /// Whether older content exists before `start_offset`
+    pub has_more: bool,
+}
+
+/// Decode one line, tolerating invalid UTF-8 and CRLF endings
+pub(crate) fn decode_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Detect a timestamp near the start of a line, as Unix milliseconds.
+/// Timestamps without an offset are taken as local time.
+pub(crate) fn detect_timestamp(line: &str) -> Option<i64> {
+    let head = match line.char_indices().nth(TIMESTAMP_SCAN_CHARS) {
+        Some((i, _)) => &line[..i],
+        None => line,
+    };
+
+    if let Some(caps) = ISO_TIMESTAMP.captures(head) {
+        let fraction = caps.get(3).map_or("0", |m| m.as_str());
+        let text = format!("{} {}.{}", &caps[1], &caps[2], fraction);
+        let naive = NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+        return match caps.get(4).map(|m| m.as_str()) {
+            Some("Z") => Some(naive.and_utc().timestamp_millis()),
+            Some(offset) => {
+                let text = format!("{} {}", text, offset.replace(':', ""));
+                DateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f %z")
+                    .ok()
+                    .map(|t| t.timestamp_millis())
+            }
+            None => Local.from_local_datetime(&naive).earliest().map(|t| t.timestamp_millis()),
+        };
+    }
+
+    if let Some(caps) = SYSLOG_TIMESTAMP.captures(head) {
+        let now = Local::now();
+        let text = format!("{} {} {} {}", now.year(), &caps[1], &caps[2], &caps[3]);
+        let mut naive = NaiveDateTime::parse_from_str(&text, "%Y %b %d %H:%M:%S").ok()?;
+        // December lines read in January belong to last year
+        if naive > now.naive_local() + chrono::Duration::days(1) {
+            let date = NaiveDate::from_ymd_opt(now.year() - 1, naive.month(), naive.day())?;
+            naive = date.and_time(naive.time());
+        }
+        return Local.from_local_datetime(&naive).earliest().map(|t| t.timestamp_millis());
+    }
+    None
+}
+
+/// Read up to `lines` complete lines ending at byte `before` (default: end of file)
+pub(crate) fn read_tail(path: &str, lines: usize, before: Option<u64>, max_bytes: Option<u64>) -> Result<LogPage, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let end = before.unwrap_or(len).min(len);
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_MAX_BYTES).max(1);
+
+    // A trailing newline terminates the last line rather than starting an empty one
+    let mut newlines_needed = lines;
+    if end > 0 {
+        let mut last = [0u8; 1];
+        file.seek(SeekFrom::Start(end - 1)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut last).map_err(|e| e.to_string())?;
+        if last[0] == b'\n' {
+            newlines_needed += 1;
+        }
+    }
+
+    // Read chunks backwards, newest first
+    let mut chunks: Vec<Vec<u8>> = vec![];
+    let mut pos = end;
+    let mut newlines = 0;
+    while pos > 0 && newlines < newlines_needed && end - pos < max_bytes {
+        let size = CHUNK_SIZE.min(pos).min(max_bytes - (end - pos));
+        pos -= size;
+        let mut chunk = vec![0u8; size as usize];
+        file.seek(SeekFrom::Start(pos)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut chunk).map_err(|e| e.to_string())?;
+        newlines += chunk.iter().filter(|b| **b == b'\n').count();
+        chunks.push(chunk);
+    }
+    let buf: Vec<u8> = chunks.into_iter().rev().flatten().collect();
+
+    let body = buf.strip_suffix(b"\n").unwrap_or(&buf);
+    let mut segments: Vec<&[u8]> = body.split(|b| *b == b'\n').collect();
+    let mut start = pos;
+    // Unless we reached the start of the file, the first segment is cut off mid-line.
+    // Keep it only when it is all we have (a single line longer than the byte cap).
+    if pos > 0 && segments.len() > 1 {
+        start += segments[0].len() as u64 + 1;
+        segments.remove(0);
+    }
+    if segments.len() > lines {
+        let skip = segments.len() - lines;
+        start += segments[..skip].iter().map(|s| s.len() as u64 + 1).sum::<u64>();
+        segments.drain(..skip);
+    }
+    if body.is_empty() {
+        segments.clear();
+    }
+    let offsets = segments
+        .iter()
+        .scan(start, |next, segment| {
+            let offset = *next;
+            *next += segment.len() as u64 + 1;
+            Some(offset)
+        })
+        .collect();
+
+    Ok(LogPage {
+        lines: segments.into_iter().map(decode_line).collect(),
+        offsets,
+        start_offset: start,
+        end_offset: end,
+        has_more: start > 0,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// Payload of the `service-log-lines` event
+#[derive(Debug, Serialize, Clone)]
+pub struct LogLinesEvent {
+    /// Increases with every event, in the order the lines were read
+    pub seq: u64,
+    pub label: String,
+    pub stream: LogStream,
+    pub lines: Vec<String>,
+    /// Byte offset where each line starts; None for lines of a file rotated away by rename
+    pub offsets: Vec<Option<u64>>,
+    /// The file was truncated or replaced; lines shown so far are stale
+    pub reset: bool,
+    /// Byte offset just past the last line. Unless `reset`, events ending at or before
+    /// the offset `follow_service_logs` returned are already covered by the tail.
+    pub end_offset: u64,
+}
+
+/// Where live following of one file starts
+#[derive(Debug, Serialize, Clone)]
+pub struct FollowPosition {
+    pub stream: LogStream,
+    pub path: String,
+    /// Read the tail with `before` set to this offset; later lines arrive as events
+    pub offset: u64,
+}
+
+/// The distinct log files of a service; stderr is left out when it shares stdout's file
+pub(crate) fn log_paths(service: &LaunchAgent) -> Vec<(LogStream, String)> {
+    let mut paths = vec![];
+    if let Some(path) = &service.standard_out_path {
+        paths.push((LogStream::Stdout, path.clone()));
+    }
+    if let Some(path) = &service.standard_error_path {
+        if !paths.iter().any(|(_, p)| p == path) {
+            paths.push((LogStream::Stderr, path.clone()));
+        }
+    }
+    paths
+}
+
+/// What follows `<name>.` in an archive: a number or a date, optionally gzipped
+static ARCHIVE_SUFFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:\d+|\d{4}-\d{2}-\d{2}(?:[-_T]\d{2,6})?)(?:\.gz)?$").unwrap());
+
+/// Rotated siblings of a log file (`app.log.1`, `app.log.2.gz`, `app.log.2024-05-01`, ...), oldest first
+pub(crate) fn rotated_files(path: &str) -> Vec<PathBuf> {
+    let path = Path::new(path);
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return vec![];
+    };
+    let prefix = format!("{}.", name);
+    let Ok(entries) = fs::read_dir(dir) else { return vec![] };
+
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.strip_prefix(&prefix))
+                .is_some_and(|suffix| ARCHIVE_SUFFIX.is_match(suffix))
+        })
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some((meta.modified().ok()?, entry.path()))
+        })
+        .collect();
+    files.sort();
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Identity of the file behind a path, to notice rename-based rotation
+#[cfg(unix)]
+fn file_id(meta: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Offset just past the file's last newline, or its length when there is none nearby
+fn last_line_start(file: &mut File) -> u64 {
+    let Ok(len) = file.metadata().map(|m| m.len()) else { return 0 };
+    let start = len.saturating_sub(CHUNK_SIZE);
+    let mut buf = vec![];
+    if file.seek(SeekFrom::Start(start)).is_err() || file.take(len - start).read_to_end(&mut buf).is_err() {
+        return len;
+    }
+    match buf.iter().rposition(|b| *b == b'\n') {
+        Some(i) => start + i as u64 + 1,
+        None if start == 0 => 0,
+        None => len,
+    }
+}
+
+/// One followed log file
+struct FollowedFile {
+    stream: LogStream,
+    path: String,
+    file: Option<File>,
+    id: Option<(u64, u64)>,
+    offset: u64,
+    /// Bytes after the last newline, waiting for the rest of the line
+    partial: Vec<u8>,
+}
+
+impl FollowedFile {
+    fn new(stream: LogStream, path: String) -> Self {
+        let mut followed = Self {
+            stream,
+            path,
+            file: None,
+            id: None,
+            offset: 0,
+            partial: vec![],
+        };
+        // Only lines written from now on are streamed; the tail comes from read_service_logs.
+        // An unfinished last line is left to the follower so it isn't split in two.
+        if followed.open() {
+            followed.offset = followed.file.as_mut().map_or(0, last_line_start);
+        }
+        followed
+    }
+
+    /// Offset just past the last line emitted; unfinished bytes are not counted
+    fn emitted_offset(&self) -> u64 {
+        self.offset - self.partial.len() as u64
+    }
+
+    fn position(&self) -> FollowPosition {
+        FollowPosition {
+            stream: self.stream,
+            path: self.path.clone(),
+            offset: self.emitted_offset(),
+        }
+    }
+
+    fn open(&mut self) -> bool {
+        let Ok(file) = File::open(&self.path) else { return false };
+        self.id = file.metadata().ok().as_ref().and_then(file_id);
+        self.file = Some(file);
+        self.offset = 0;
+        true
+    }
+
+    /// Read what was appended since the last poll into `lines`, at most `FOLLOW_READ_LIMIT`.
+    /// Returns whether more is left to read.
+    fn drain(&mut self, lines: &mut Vec<(Option<u64>, String)>) -> Result<bool, String> {
+        let Some(file) = self.file.as_mut() else { return Ok(false) };
+        let len = file.metadata().map_err(|e| e.to_string())?.len();
+        if len <= self.offset {
+            return Ok(false);
+        }
+        let size = (len - self.offset).min(FOLLOW_READ_LIMIT);
+        let mut buf = vec![0u8; size as usize];
+        file.seek(SeekFrom::Start(self.offset)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut buf).map_err(|e| e.to_string())?;
+        let mut line_start = self.emitted_offset();
+        self.offset += size;
+
+        self.partial.extend_from_slice(&buf);
+        if let Some(last_newline) = self.partial.iter().rposition(|b| *b == b'\n') {
+            let rest = self.partial.split_off(last_newline + 1);
+            for segment in self.partial[..last_newline].split(|b| *b == b'\n') {
+                lines.push((Some(line_start), decode_line(segment)));
+                line_start += segment.len() as u64 + 1;
+            }
+            self.partial = rest;
+        }
+        Ok(self.offset < len)
+    }
+
+    fn flush_partial(&mut self, lines: &mut Vec<(Option<u64>, String)>) {
+        if !self.partial.is_empty() {
+            let line_start = self.emitted_offset();
+            lines.push((Some(line_start), decode_line(&std::mem::take(&mut self.partial))));
+        }
+    }
+
+    /// New lines with their start offsets since the last poll, and whether earlier output was invalidated
+    fn poll(&mut self) -> (Vec<(Option<u64>, String)>, bool) {
+        let mut lines = vec![];
+        let mut reset = false;
+
+        if self.file.is_none() {
+            // Missing until now, e.g. removed by rotation: everything in it is new
+            if self.open() {
+                reset = true;
+            }
+        } else if self.file.as_ref().and_then(|f| f.metadata().ok()).is_some_and(|m| m.len() < self.offset) {
+            // Truncated in place (copy-truncate rotation or clear_service_logs)
+            self.offset = 0;
+            self.partial.clear();
+            reset = true;
+        }
+        if let Err(e) = self.drain(&mut lines) {
+            eprintln!("Failed to read {}: {}", self.path, e);
+        }
+
+        let current_id = fs::metadata(&self.path).ok().as_ref().and_then(file_id);
+        let replaced = self.file.is_some()
+            && (!std::path::Path::new(&self.path).exists() || (self.id.is_some() && current_id != self.id));
+        if replaced {
+            // Rotated by rename: read the old handle to its end, then continue with the new file
+            loop {
+                match self.drain(&mut lines) {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(e) => {
+                        eprintln!("Failed to read {}: {}", self.path, e);
+                        break;
+                    }
+                }
+            }
+            self.flush_partial(&mut lines);
+            // Offsets into the old file mean nothing for the new one
+            for (offset, _) in lines.iter_mut() {
+                *offset = None;
+            }
+            self.file = None;
+            if self.open() {
+                reset = true;
+                if let Err(e) = self.drain(&mut lines) {
+                    eprintln!("Failed to read {}: {}", self.path, e);
+                }
+            }
+        }
+        (lines, reset)
+    }
+}
+
+struct Follow {
+    subscribers: usize,
+    stop: Arc<AtomicBool>,
+    /// Shared with the follow thread, which holds the lock while polling and emitting
+    files: Arc<Mutex<Vec<FollowedFile>>>,
+}
+
+/// Managed state with the log followers running per service
+#[derive(Default)]
+pub struct LogFollowers {
+    follows: Mutex<HashMap<String, Follow>>,
+}
+
+fn run_follow(app: AppHandle, label: String, files: Arc<Mutex<Vec<FollowedFile>>>, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        if let Ok(mut files) = files.lock() {
+            for followed in files.iter_mut() {
+                let (lines, reset) = followed.poll();
+                if lines.is_empty() && !reset {
+                    continue;
+                }
+                let (offsets, lines) = lines.into_iter().unzip();
+                let event = LogLinesEvent {
+                    seq: NEXT_EVENT_SEQ.fetch_add(1, Ordering::Relaxed),
+                    label: label.clone(),
+                    stream: followed.stream,
+                    lines,
+                    offsets,
+                    reset,
+                    end_offset: followed.emitted_offset(),
+                };
+                let _ = app.emit("service-log-lines", &event);
+            }
+        }
+        std::thread::sleep(FOLLOW_INTERVAL);
+    }
+}
+
+/// Stream new stdout/stderr lines of a service as `service-log-lines` events.
+/// Listen for the events first, then call this and read the tail up to the returned
+/// offsets, so no line is lost or shown twice. Each call must be paired with
+/// `unfollow_service_logs`.
+#[tauri::command]
+pub fn follow_service_logs(app: AppHandle, label: String) -> Result<Vec<FollowPosition>, String> {
+    let followers = app.state::<LogFollowers>();
+    let mut follows = followers.follows.lock().map_err(|e| e.to_string())?;
+    if let Some(follow) = follows.get_mut(&label) {
+        follow.subscribers += 1;
+        let files = follow.files.lock().map_err(|e| e.to_string())?;
+        return Ok(files.iter().map(FollowedFile::position).collect());
+    }
+
+    let paths = log_paths(&find_service(&label)?);
+    if paths.is_empty() {
+        return Err(format!("Service '{}' has no log files configured", label));
+    }
+    let files: Vec<FollowedFile> = paths.into_iter().map(|(stream, path)| FollowedFile::new(stream, path)).collect();
+    let positions = files.iter().map(FollowedFile::position).collect();
+    let files = Arc::new(Mutex::new(files));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    follows.insert(
+        label.clone(),
+        Follow {
+            subscribers: 1,
+            stop: stop.clone(),
+            files: files.clone(),
+        },
+    );
+    let thread_app = app.clone();
+    std::thread::spawn(move || run_follow(thread_app, label, files, stop));
+    Ok(positions)
+}
+
+#[tauri::command]
+pub fn unfollow_service_logs(app: AppHandle, label: String) -> Result<String, String> {
+    let followers = app.state::<LogFollowers>();
+    let mut follows = followers.follows.lock().map_err(|e| e.to_string())?;
+    if let Some(follow) = follows.get_mut(&label) {
+        follow.subscribers = follow.subscribers.saturating_sub(1);
+        if follow.subscribers == 0 {
+            follow.stop.store(true, Ordering::Relaxed);
+            follows.remove(&label);
+        }
+    }
+    Ok(format!("Stopped following logs of {}", label))
+}
+
+#[tauri::command]
+pub fn get_service_logs(log_path: String, lines: usize) -> Result<Vec<String>, String> {
+    Ok(read_tail(&log_path, lines, None, None)?.lines)
+}
+
+/// Page backwards through a log: omit `before` for the newest lines, then pass the
+/// previous page's `start_offset`
+#[tauri::command]
+pub fn read_service_logs(
+    log_path: String,
+    lines: usize,
+    before: Option<u64>,
+    max_bytes: Option<u64>,
+) -> Result<LogPage, String> {
+    read_tail(&log_path, lines, before, max_bytes)
+}
+
+/// A log line tagged with the stream it came from
+#[derive(Debug, Serialize, Clone)]
+pub struct TaggedLine {
+    pub stream: LogStream,
+    pub line: String,
+    /// Unix milliseconds, detected on the line or inherited from the line before
+    pub timestamp: Option<i64>,
+    /// The timestamp was inherited rather than detected on the line
+    #[serde(skip)]
+    inherited: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MergedLogPage {
+    pub lines: Vec<TaggedLine>,
+    /// Whether lines are in time order; otherwise streams without timestamps follow the rest
+    pub interleaved: bool,
+}
+
+/// Tag lines with timestamps; leading lines take the first timestamp found
+fn tag_lines(stream: LogStream, lines: Vec<String>) -> Vec<TaggedLine> {
+    let mut last = None;
+    let mut tagged: Vec<TaggedLine> = lines
+        .into_iter()
+        .map(|line| {
+            let detected = detect_timestamp(&line);
+            last = detected.or(last);
+            TaggedLine {
+                stream,
+                line,
+                timestamp: last,
+                inherited: detected.is_none(),
+            }
+        })
+        .collect();
+    let first = tagged.iter().find_map(|l| l.timestamp);
+    for line in tagged.iter_mut().take_while(|l| l.timestamp.is_none()) {
+        line.timestamp = first;
+    }
+    tagged
+}
+
+/// Merge streams by timestamp while keeping each stream's own order
+fn merge_by_timestamp(streams: Vec<Vec<TaggedLine>>) -> Vec<TaggedLine> {
+    let mut iters: Vec<_> = streams.into_iter().map(|s| s.into_iter().peekable()).collect();
+    let mut merged = vec![];
+    let mut last: Option<usize> = None;
+    loop {
+        // Continuation lines (stack traces, wrapped output) stay with the line before them
+        if let Some(i) = last {
+            if iters[i].peek().is_some_and(|l| l.inherited) {
+                merged.extend(iters[i].next());
+                continue;
+            }
+        }
+        let next = iters
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, it)| it.peek().and_then(|l| l.timestamp).map(|t| (t, i)))
+            .min();
+        let Some((_, i)) = next else { break };
+        merged.extend(iters[i].next());
+        last = Some(i);
+    }
+    // Only streams without a single timestamp are left
+    merged.extend(iters.into_iter().flatten());
+    merged
+}
+
+/// The last `lines` lines of a service's stdout and stderr combined, each tagged with its stream.
+/// `stdout_before`/`stderr_before` end each stream at a follow position.
+#[tauri::command]
+pub fn read_merged_service_logs(
+    label: String,
+    lines: usize,
+    stdout_before: Option<u64>,
+    stderr_before: Option<u64>,
+) -> Result<MergedLogPage, String> {
+    let paths = log_paths(&find_service(&label)?);
+    if paths.is_empty() {
+        return Err(format!("Service '{}' has no log files configured", label));
+    }
+
+    let mut streams = vec![];
+    for (stream, path) in paths {
+        if Path::new(&path).exists() {
+            let before = match stream {
+                LogStream::Stdout => stdout_before,
+                LogStream::Stderr => stderr_before,
+            };
+            streams.push(tag_lines(stream, read_tail(&path, lines, before, None)?.lines));
+        }
+    }
+    let interleaved = streams.iter().flatten().all(|l| l.timestamp.is_some());
+    let mut merged = merge_by_timestamp(streams);
+    if merged.len() > lines {
+        merged.drain(..merged.len() - lines);
+    }
+    Ok(MergedLogPage {
+        lines: merged,
+        interleaved,
+    })
+}
+
+#[tauri::command]
+pub fn clear_service_logs(log_path: String) -> Result<String, String> {
+    fs::write(&log_path, "").map_err(|e| e.to_string())?;
+    Ok("Logs cleared successfully".to_string())
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LogSearchQuery {
+    pub pattern: String,
+    /// Treat `pattern` as a regular expression instead of a plain substring
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Lines of context before and after each match
+    pub before: usize,
+    pub after: usize,
+    /// Keep at most this many matches, the most recent ones
+    pub max_matches: usize,
+    /// Unix timestamps (seconds); lines without a detectable time are skipped when set
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    /// Also search rotated files next to the current ones
+    pub include_rotated: bool,
+}
+
+impl Default for LogSearchQuery {
+    fn default() -> Self {
+        Self {
+            pattern: String::new(),
+            regex: false,
+            case_sensitive: false,
+            before: 0,
+            after: 0,
+            max_matches: DEFAULT_MAX_MATCHES,
+            since: None,
+            until: None,
+            include_rotated: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogMatch {
+    pub stream: LogStream,
+    pub file: String,
+    /// 1-based line number within `file`
+    pub line_number: usize,
+    pub line: String,
+    /// Unix milliseconds, detected on the line or inherited from the line before
+    pub timestamp: Option<i64>,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogSearchResult {
+    pub matches: Vec<LogMatch>,
+    /// More lines matched than `max_matches`; the oldest were dropped
+    pub truncated: bool,
+    pub files_searched: Vec<String>,
+}
+
+fn search_file(
+    path: &Path,
+    stream: LogStream,
+    matcher: &Regex,
+    query: &LogSearchQuery,
+    matches: &mut VecDeque<LogMatch>,
+    truncated: &mut bool,
+) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut reader: Box<dyn BufRead> = if path.extension().is_some_and(|e| e == "gz") {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    let since = query.since.map(|s| s as i64 * 1000);
+    let until = query.until.map(|s| s as i64 * 1000);
+    let file_name = path.to_string_lossy().into_owned();
+
+    let mut context: VecDeque<String> = VecDeque::with_capacity(query.before);
+    // Indices into `matches`, counted from the front, still collecting `after` lines
+    let mut pending: Vec<usize> = vec![];
+    let mut last_timestamp = None;
+    let mut buf = vec![];
+    let mut line_number = 0;
+
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf).map_err(|e| e.to_string())? == 0 {
+            break;
+        }
+        line_number += 1;
+        let line = decode_line(buf.strip_suffix(b"\n").unwrap_or(&buf));
+
+        pending.retain(|&i| match matches.get_mut(i) {
+            Some(m) if m.after.len() < query.after => {
+                m.after.push(line.clone());
+                m.after.len() < query.after
+            }
+            _ => false,
+        });
+
+        last_timestamp = detect_timestamp(&line).or(last_timestamp);
+        let in_range = last_timestamp.map_or(since.is_none() && until.is_none(), |t| {
+            since.is_none_or(|s| t >= s) && until.is_none_or(|u| t <= u)
+        });
+
+        if in_range && matcher.is_match(&line) {
+            if matches.len() >= query.max_matches.max(1) {
+                matches.pop_front();
+                pending.retain_mut(|i| match i.checked_sub(1) {
+                    Some(shifted) => {
+                        *i = shifted;
+                        true
+                    }
+                    None => false,
+                });
+                *truncated = true;
+            }
+            matches.push_back(LogMatch {
+                stream,
+                file: file_name.clone(),
+                line_number,
+                timestamp: last_timestamp,
+                line: line.clone(),
+                before: context.iter().cloned().collect(),
+                after: vec![],
+            });
+            if query.after > 0 {
+                pending.push(matches.len() - 1);
+            }
+        }
+
+        if query.before > 0 {
+            if context.len() == query.before {
+                context.pop_front();
+            }
+            context.push_back(line);
+        }
+    }
+    Ok(())
+}
+
+/// Search a service's stdout and stderr, including rotated files, for a substring or regex
+#[tauri::command(async)]
+pub fn search_service_logs(label: String, query: LogSearchQuery) -> Result<LogSearchResult, String> {
+    if query.pattern.is_empty() {
+        return Err("Search pattern is empty".to_string());
+    }
+    let pattern = if query.regex {
+        query.pattern.clone()
+    } else {
+        regex::escape(&query.pattern)
+    };
+    let matcher = RegexBuilder::new(&pattern)
+        .case_insensitive(!query.case_sensitive)
+        .build()
+        .map_err(|e| format!("Invalid regex: {}", e))?;
+
+    let paths = log_paths(&find_service(&label)?);
+    if paths.is_empty() {
+        return Err(format!("Service '{}' has no log files configured", label));
+    }
+
+    let mut result = LogSearchResult {
+        matches: vec![],
+        truncated: false,
+        files_searched: vec![],
+    };
+    for (stream, path) in paths {
+        let mut files = if query.include_rotated { rotated_files(&path) } else { vec![] };
+        files.push(PathBuf::from(&path));
+        // Each stream keeps its own most recent matches; the cap applies again after merging
+        let mut matches = VecDeque::new();
+        for file in files.iter().filter(|f| f.exists()) {
+            search_file(file, stream, &matcher, &query, &mut matches, &mut result.truncated)?;
+            result.files_searched.push(file.to_string_lossy().into_owned());
+        }
+        result.matches.extend(matches);
+    }
+
+    // Interleave stdout and stderr in time order where timestamps are known
+    if result.matches.iter().all(|m| m.timestamp.is_some()) {
+        result.matches.sort_by_key(|m| m.timestamp);
+    }
+    let max_matches = query.max_matches.max(1);
+    if result.matches.len() > max_matches {
+        let excess = result.matches.len() - max_matches;
+        result.matches.drain(..excess);
+        result.truncated = true;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Timelike, Utc};
+
+    #[test]
+    fn detect_timestamp_iso_with_offset() {
+        let expected = Utc.with_ymd_and_hms(2024, 5, 1, 4, 0, 0).unwrap().timestamp_millis();
+        assert_eq!(detect_timestamp("2024-05-01T12:00:00+08:00 started"), Some(expected));
+        assert_eq!(detect_timestamp("[2024-05-01 12:00:00 +0800] started"), Some(expected));
+        assert_eq!(detect_timestamp("2024-05-01T04:00:00.250Z started"), Some(expected + 250));
+    }
+
+    #[test]
+    fn detect_timestamp_python_comma_millis() {
+        let expected = Local.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap().timestamp_millis() + 123;
+        assert_eq!(detect_timestamp("2024-05-01 12:00:00,123 - INFO - started"), Some(expected));
+    }
+
+    #[test]
+    fn detect_timestamp_syslog_uses_current_or_last_year() {
+        let past = (Local::now() - Duration::days(30)).with_nanosecond(0).unwrap();
+        let line = format!("{} host app[1]: started", past.format("%b %e %H:%M:%S"));
+        assert_eq!(detect_timestamp(&line), Some(past.timestamp_millis()));
+
+        // Until the last days of December, "Dec 31" can only be last year's
+        let now = Local::now();
+        if now.month() < 12 {
+            let expected = Local.with_ymd_and_hms(now.year() - 1, 12, 31, 23, 59, 59).unwrap().timestamp_millis();
+            assert_eq!(detect_timestamp("Dec 31 23:59:59 host app[1]: started"), Some(expected));
+        }
+    }
+
+    #[test]
+    fn detect_timestamp_none_without_timestamp() {
+        assert_eq!(detect_timestamp("started on port 8080"), None);
+        assert_eq!(detect_timestamp(""), None);
+        let late = format!("{} 2024-05-01 12:00:00", "x".repeat(TIMESTAMP_SCAN_CHARS));
+        assert_eq!(detect_timestamp(&late), None);
+    }
+
+    #[test]
+    fn archive_suffix_matches_numbers_and_dates_only() {
+        for suffix in ["1", "12.gz", "20240501", "2024-05-01", "2024-05-01.gz", "2024-05-01-1200"] {
+            assert!(ARCHIVE_SUFFIX.is_match(suffix), "{}", suffix);
+        }
+        for suffix in ["err", "1.tmp", "old.gz", "gz", "1.gz.bak", ""] {
+            assert!(!ARCHIVE_SUFFIX.is_match(suffix), "{}", suffix);
+        }
+    }
+
+    fn tagged(stream: LogStream, lines: &[&str]) -> Vec<TaggedLine> {
+        tag_lines(stream, lines.iter().map(|l| l.to_string()).collect())
+    }
+
+    fn merged_text(streams: Vec<Vec<TaggedLine>>) -> Vec<String> {
+        merge_by_timestamp(streams).into_iter().map(|l| l.line).collect()
+    }
+
+    #[test]
+    fn merge_interleaves_by_timestamp() {
+        let stdout = tagged(LogStream::Stdout, &["2024-05-01T12:00:00Z a", "2024-05-01T12:00:02Z c"]);
+        let stderr = tagged(LogStream::Stderr, &["2024-05-01T12:00:01Z b", "2024-05-01T12:00:03Z d"]);
+        assert_eq!(
+            merged_text(vec![stdout, stderr]),
+            ["2024-05-01T12:00:00Z a", "2024-05-01T12:00:01Z b", "2024-05-01T12:00:02Z c", "2024-05-01T12:00:03Z d"]
+        );
+    }
+
+    #[test]
+    fn merge_keeps_continuation_lines_with_their_stream() {
+        let stdout = tagged(LogStream::Stdout, &["2024-05-01T12:00:00Z request", "2024-05-01T12:00:05Z done"]);
+        let stderr = tagged(LogStream::Stderr, &["2024-05-01T12:00:00Z panic", "  at main.rs:1", "  at lib.rs:2"]);
+        assert_eq!(
+            merged_text(vec![stdout, stderr]),
+            [
+                "2024-05-01T12:00:00Z request",
+                "2024-05-01T12:00:00Z panic",
+                "  at main.rs:1",
+                "  at lib.rs:2",
+                "2024-05-01T12:00:05Z done",
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_places_leading_lines_by_the_first_timestamp() {
+        let stdout = tagged(LogStream::Stdout, &["2024-05-01T12:00:00Z a", "2024-05-01T12:00:02Z c"]);
+        let stderr = tagged(LogStream::Stderr, &["wrapped tail", "2024-05-01T12:00:01Z b"]);
+        assert_eq!(
+            merged_text(vec![stdout, stderr]),
+            ["2024-05-01T12:00:00Z a", "wrapped tail", "2024-05-01T12:00:01Z b", "2024-05-01T12:00:02Z c"]
+        );
+    }
+
+    #[test]
+    fn merge_appends_streams_without_timestamps() {
+        let stdout = tagged(LogStream::Stdout, &["plain", "output"]);
+        let stderr = tagged(LogStream::Stderr, &["2024-05-01T12:00:00Z a", "2024-05-01T12:00:01Z b"]);
+        assert_eq!(
+            merged_text(vec![stdout, stderr]),
+            ["2024-05-01T12:00:00Z a", "2024-05-01T12:00:01Z b", "plain", "output"]
+        );
+    }
+}
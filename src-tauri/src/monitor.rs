@@ -0,0 +1,247 @@
+//! Background health monitor.
+//!
+//! Instead of the UI polling each service, a single backend thread tracks the run
+//! state of every service and runs its configured checks on a per-service
+//! interval. The latest result and a short history are kept in managed state, and
+//! every transition is published once so the window, tray and notifications all
+//! see the same picture. Observations are also persisted for uptime statistics.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::crash_loop::CrashLoopMonitor;
+use crate::health::{self, HealthResult, HealthStatus, ServiceChecks};
+use crate::uptime::UptimeRecorder;
+use crate::{collect_services, load_all_metadata, notify, remediation, tray, unix_now, webhooks, LaunchAgent, ServiceState};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 15;
+/// Samples kept per service
+const HISTORY_LIMIT: usize = 30;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct HealthSample {
+    pub checked_at: u64,
+    pub status: HealthStatus,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ServiceHealth {
+    pub label: String,
+    pub state: ServiceState,
+    /// Worst status across the configured checks; None when stopped or unchecked
+    pub status: Option<HealthStatus>,
+    pub checks: ServiceChecks,
+    pub consecutive_failures: u32,
+    pub history: VecDeque<HealthSample>,
+    pub interval_secs: u64,
+    /// Unix timestamp (seconds) of the next scheduled check
+    pub next_check_at: u64,
+}
+
+/// A transition observed by the monitor
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ServiceEvent {
+    StateChanged {
+        label: String,
+        from: ServiceState,
+        to: ServiceState,
+    },
+    HealthChanged {
+        label: String,
+        from: Option<HealthStatus>,
+        to: HealthStatus,
+        result: HealthResult,
+    },
+    /// A check came back unhealthy; sent for every failure in a row
+    CheckFailed {
+        label: String,
+        consecutive_failures: u32,
+        result: HealthResult,
+    },
+    /// The process died and launchd respawned it
+    Crashed {
+        label: String,
+        exit_status: Option<i32>,
+    },
+    /// The process exited with a non-zero status and was not respawned
+    Exited {
+        label: String,
+        exit_code: i32,
+    },
+}
+
+impl ServiceEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ServiceEvent::StateChanged { .. } => "service-state-changed",
+            ServiceEvent::HealthChanged { .. } => "service-health-changed",
+            ServiceEvent::CheckFailed { .. } => "service-check-failed",
+            ServiceEvent::Crashed { .. } => "service-crashed",
+            ServiceEvent::Exited { .. } => "service-exited",
+        }
+    }
+}
+
+/// Managed state shared between the monitor thread and commands
+#[derive(Default)]
+pub struct HealthMonitor {
+    services: Mutex<HashMap<String, ServiceHealth>>,
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        tick(&app);
+        std::thread::sleep(TICK_INTERVAL);
+    });
+}
+
+/// Deliver an event to everything that reacts to service changes
+pub(crate) fn publish(app: &AppHandle, event: ServiceEvent) {
+    if matches!(event, ServiceEvent::StateChanged { .. }) {
+        tray::refresh(app);
+    }
+    notify::handle_event(app, &event);
+    remediation::handle_event(app, &event);
+    webhooks::dispatch(&event);
+    let _ = app.emit(event.name(), event);
+}
+
+fn tick(app: &AppHandle) {
+    let Ok(services) = collect_services() else { return };
+    let metadata = load_all_metadata();
+    let now = unix_now();
+
+    let mut events = vec![];
+    let mut due: Vec<&LaunchAgent> = vec![];
+    {
+        let crash_monitor = app.state::<CrashLoopMonitor>();
+        let recorder = app.state::<UptimeRecorder>();
+        let monitor = app.state::<HealthMonitor>();
+        let Ok(mut tracked) = monitor.services.lock() else { return };
+
+        for service in &services {
+            let mut state = service.state;
+            if service.is_loaded && crash_monitor.is_looping(&service.label) {
+                state = ServiceState::CrashLooping;
+            }
+            let interval_secs = metadata
+                .get(&service.label)
+                .and_then(|m| m.check_interval_secs)
+                .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS)
+                .max(1);
+
+            let entry = tracked.entry(service.label.clone()).or_insert_with(|| ServiceHealth {
+                label: service.label.clone(),
+                state,
+                status: None,
+                checks: ServiceChecks::default(),
+                consecutive_failures: 0,
+                history: VecDeque::new(),
+                interval_secs,
+                next_check_at: now,
+            });
+            entry.interval_secs = interval_secs;
+
+            if entry.state != state {
+                events.push(ServiceEvent::StateChanged {
+                    label: service.label.clone(),
+                    from: entry.state,
+                    to: state,
+                });
+                entry.state = state;
+                // Check (and record) right away after a transition
+                entry.next_check_at = now;
+            }
+
+            if state == ServiceState::Stopped {
+                entry.status = None;
+                entry.checks = ServiceChecks::default();
+                entry.consecutive_failures = 0;
+            }
+            if entry.next_check_at <= now {
+                entry.next_check_at = now + interval_secs;
+                if state == ServiceState::Stopped {
+                    recorder.record(&service.label, state, None);
+                } else {
+                    due.push(service);
+                }
+            }
+        }
+
+        // Forget services whose plist was removed
+        tracked.retain(|label, _| services.iter().any(|s| &s.label == label));
+    }
+
+    // Checks can take seconds each, so run them in parallel and outside the lock
+    let results: Vec<(String, ServiceChecks)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = due
+            .iter()
+            .map(|service| scope.spawn(move || (service.label.clone(), health::check_service(service))))
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    });
+
+    if !results.is_empty() {
+        let recorder = app.state::<UptimeRecorder>();
+        let monitor = app.state::<HealthMonitor>();
+        if let Ok(mut tracked) = monitor.services.lock() {
+            for (label, checks) in results {
+                let Some(entry) = tracked.get_mut(&label) else { continue };
+                // The service may have been stopped while its checks ran
+                if entry.state == ServiceState::Stopped {
+                    continue;
+                }
+                let status = checks.summary().map(|r| r.status);
+                recorder.record(&label, entry.state, status);
+                if let Some(summary) = checks.summary().cloned() {
+                    entry.history.push_back(HealthSample {
+                        checked_at: summary.checked_at,
+                        status: summary.status,
+                        latency_ms: summary.latency_ms,
+                    });
+                    while entry.history.len() > HISTORY_LIMIT {
+                        entry.history.pop_front();
+                    }
+                    if summary.status == HealthStatus::Unhealthy {
+                        entry.consecutive_failures += 1;
+                        events.push(ServiceEvent::CheckFailed {
+                            label: label.clone(),
+                            consecutive_failures: entry.consecutive_failures,
+                            result: summary.clone(),
+                        });
+                    } else {
+                        entry.consecutive_failures = 0;
+                    }
+                    if entry.status != Some(summary.status) {
+                        events.push(ServiceEvent::HealthChanged {
+                            label: label.clone(),
+                            from: entry.status,
+                            to: summary.status,
+                            result: summary.clone(),
+                        });
+                        entry.status = Some(summary.status);
+                    }
+                }
+                entry.checks = checks;
+            }
+        };
+    }
+
+    for event in events {
+        publish(app, event);
+    }
+}
+
+#[tauri::command]
+pub fn get_health_status(monitor: tauri::State<'_, HealthMonitor>) -> Result<Vec<ServiceHealth>, String> {
+    let services = monitor.services.lock().map_err(|e| e.to_string())?;
+    let mut list: Vec<ServiceHealth> = services.values().cloned().collect();
+    list.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(list)
+}
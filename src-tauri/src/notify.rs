@@ -0,0 +1,232 @@
+//! Native desktop notifications.
+//!
+//! Service events from the health monitor and crash sampler are turned into
+//! notifications here, filtered through `NotificationSettings`: global and
+//! per-service mute, global and per-service quiet hours, and a debounce so a
+//! flapping service only notifies once per window.
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::health::HealthStatus;
+use crate::monitor::ServiceEvent;
+use crate::{get_app_settings, load_all_metadata, unix_now, ServiceState};
+
+/// Local time window ("HH:MM") during which nothing is shown; may wrap past midnight
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn contains(&self, now: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(&self.start, "%H:%M"),
+            NaiveTime::parse_from_str(&self.end, "%H:%M"),
+        ) else {
+            return false;
+        };
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+/// Notification preferences stored in `AppSettings`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Silence every notification
+    pub muted: bool,
+    /// Labels whose notifications are silenced
+    pub muted_services: Vec<String>,
+    pub quiet_hours: Option<QuietHours>,
+    /// Per-service quiet hours, used instead of `quiet_hours` for that service
+    pub service_quiet_hours: HashMap<String, QuietHours>,
+    pub on_crash: bool,
+    pub on_exit: bool,
+    pub on_health_failure: bool,
+    pub on_recovery: bool,
+    /// Consecutive failed health checks before notifying
+    pub health_failure_threshold: u32,
+    /// Minimum time between two notifications of the same kind for a service
+    pub debounce_secs: u64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            muted_services: vec![],
+            quiet_hours: None,
+            service_quiet_hours: HashMap::new(),
+            on_crash: true,
+            on_exit: true,
+            on_health_failure: true,
+            on_recovery: true,
+            health_failure_threshold: 3,
+            debounce_secs: 300,
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn silenced(&self, label: &str, now: NaiveTime) -> bool {
+        let quiet_hours = self.service_quiet_hours.get(label).or(self.quiet_hours.as_ref());
+        self.muted || self.muted_services.iter().any(|l| l == label) || quiet_hours.is_some_and(|q| q.contains(now))
+    }
+}
+
+/// Managed state for debouncing and pairing failures with recoveries
+#[derive(Default)]
+pub struct Notifier {
+    /// Services with an unresolved failure, so recovery can be reported
+    alerted: Mutex<HashSet<String>>,
+    /// Last notification time per "label:kind"
+    last_sent: Mutex<HashMap<String, u64>>,
+}
+
+pub(crate) fn send(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+}
+
+/// Send a notification about a service unless it is muted or in quiet hours
+pub(crate) fn send_for_service(app: &AppHandle, label: &str, title: &str, body: &str) {
+    let settings = get_app_settings().unwrap_or_default().notifications;
+    if !settings.silenced(label, Local::now().time()) {
+        send(app, title, body);
+    }
+}
+
+fn display_name(label: &str) -> String {
+    load_all_metadata()
+        .remove(label)
+        .and_then(|m| m.display_name)
+        .unwrap_or_else(|| label.to_string())
+}
+
+/// Turn a service event into a notification, if the settings call for one
+pub(crate) fn handle_event(app: &AppHandle, event: &ServiceEvent) {
+    let settings = get_app_settings().unwrap_or_default().notifications;
+    let notifier = app.state::<Notifier>();
+
+    // (label, kind, title, body, whether this kind is enabled)
+    let (label, kind, title, body, enabled) = match event {
+        ServiceEvent::Crashed { label, exit_status } => {
+            let status = exit_status.map_or("unknown".to_string(), |s| s.to_string());
+            let body = format!("{} crashed and was restarted (exit status {})", display_name(label), status);
+            (label, "crash", "Service crashed", body, settings.on_crash)
+        }
+        ServiceEvent::Exited { label, exit_code } => {
+            let body = format!("{} exited with status {}", display_name(label), exit_code);
+            (label, "exit", "Service exited", body, settings.on_exit)
+        }
+        ServiceEvent::CheckFailed {
+            label,
+            consecutive_failures,
+            result,
+        } if *consecutive_failures == settings.health_failure_threshold.max(1) => {
+            let body = format!(
+                "{} failed {} health checks in a row: {}",
+                display_name(label),
+                consecutive_failures,
+                result.message
+            );
+            (label, "health", "Service unhealthy", body, settings.on_health_failure)
+        }
+        ServiceEvent::HealthChanged {
+            label,
+            from: Some(HealthStatus::Unhealthy),
+            to,
+            ..
+        } if *to != HealthStatus::Unhealthy => {
+            let body = format!("{} is healthy again", display_name(label));
+            (label, "recovery", "Service recovered", body, settings.on_recovery)
+        }
+        ServiceEvent::StateChanged {
+            label,
+            from: ServiceState::CrashLooping,
+            to: ServiceState::Running,
+        } => {
+            let body = format!("{} is no longer crash looping", display_name(label));
+            (label, "recovery", "Service recovered", body, settings.on_recovery)
+        }
+        _ => return,
+    };
+
+    if kind == "recovery" {
+        // Only report recoveries for failures that were actually notified
+        let was_alerted = notifier.alerted.lock().is_ok_and(|mut alerted| alerted.remove(label));
+        if !was_alerted {
+            return;
+        }
+    } else if enabled {
+        if let Ok(mut alerted) = notifier.alerted.lock() {
+            alerted.insert(label.clone());
+        }
+    }
+    if !enabled {
+        return;
+    }
+
+    if settings.silenced(label, Local::now().time()) {
+        return;
+    }
+    let now = unix_now();
+    if let Ok(mut last_sent) = notifier.last_sent.lock() {
+        let key = format!("{}:{}", label, kind);
+        if last_sent.get(&key).is_some_and(|t| now.saturating_sub(*t) < settings.debounce_secs) {
+            return;
+        }
+        last_sent.insert(key, now);
+    }
+    send(app, title, &body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet(start: &str, end: &str) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn at(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn quiet_hours_wrap_past_midnight() {
+        let night = quiet("22:00", "07:00");
+        assert!(night.contains(at("23:30")));
+        assert!(night.contains(at("06:59")));
+        assert!(!night.contains(at("07:00")));
+        assert!(!night.contains(at("12:00")));
+        assert!(!quiet("bad", "07:00").contains(at("23:30")));
+    }
+
+    #[test]
+    fn service_quiet_hours_replace_the_global_window() {
+        let settings = NotificationSettings {
+            quiet_hours: Some(quiet("22:00", "07:00")),
+            service_quiet_hours: HashMap::from([("db".to_string(), quiet("12:00", "13:00"))]),
+            ..Default::default()
+        };
+        assert!(settings.silenced("web", at("23:00")));
+        assert!(!settings.silenced("db", at("23:00")));
+        assert!(settings.silenced("db", at("12:30")));
+        assert!(!settings.silenced("web", at("12:30")));
+    }
+}
@@ -0,0 +1,565 @@
+//! Protocol-aware probes.
+//!
+//! A TCP connect only proves that something is listening. These probes speak just
+//! enough of each protocol to tell a working server from a wedged one: a WebSocket
+//! upgrade, Redis PING, the Postgres SSLRequest exchange, the MySQL server
+//! greeting, and a TLS handshake that reports when the certificate expires.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use crate::health::{classify_io_error, CheckFailure, FailureCategory, HealthResult, HealthStatus};
+use crate::LaunchAgent;
+
+const DEFAULT_PROBE_TIMEOUT_MS: u64 = 2000;
+const DEFAULT_WARN_DAYS: u32 = 14;
+/// Fixed handshake nonce; the probe only checks that the server agrees to upgrade
+const WEBSOCKET_KEY: &str = "dGhlIHNhbXBsZSBub25jZQ==";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProbeKind {
+    WebSocket {
+        #[serde(default = "default_path")]
+        path: String,
+        /// Use `wss://`
+        #[serde(default)]
+        tls: bool,
+    },
+    Redis {
+        password: Option<String>,
+    },
+    Postgres,
+    Mysql,
+    /// Handshake with the server and report certificate expiry
+    TlsCert {
+        /// Report degraded when the certificate expires within this many days
+        warn_days: Option<u32>,
+    },
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Per-service protocol probe stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProbeSpec {
+    #[serde(flatten)]
+    pub kind: ProbeKind,
+    /// Defaults to loopback, or the `health_url` host for TLS probes
+    pub host: Option<String>,
+    /// Defaults to the service's `port`, or the `health_url` port for TLS probes
+    pub port: Option<u16>,
+    pub timeout_ms: Option<u64>,
+}
+
+impl ProbeSpec {
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_PROBE_TIMEOUT_MS))
+    }
+
+    /// Host and port to probe, filling gaps from the service
+    fn target(&self, agent: &LaunchAgent) -> Result<(String, u16), CheckFailure> {
+        let url = agent
+            .health_url
+            .as_deref()
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .filter(|url| url.scheme() == "https");
+        let from_url = matches!(self.kind, ProbeKind::TlsCert { .. }) && url.is_some();
+
+        let host = match (&self.host, &url) {
+            (Some(host), _) => host.clone(),
+            (None, Some(url)) if from_url => url.host_str().unwrap_or("127.0.0.1").to_string(),
+            _ => "127.0.0.1".to_string(),
+        };
+        let port = match (self.port, &url) {
+            (Some(port), _) => port,
+            (None, Some(url)) if from_url => url.port_or_known_default().unwrap_or(443),
+            _ => agent.service_port().ok_or_else(|| {
+                CheckFailure::new(FailureCategory::InvalidConfig, "Probe has no port and the service has none")
+            })?,
+        };
+        Ok((host, port))
+    }
+}
+
+fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, CheckFailure> {
+    let addrs: Vec<_> = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| CheckFailure::new(FailureCategory::Dns, format!("Cannot resolve {}: {}", host, e)))?
+        .collect();
+    let mut last_error = CheckFailure::new(FailureCategory::Dns, format!("No address for {}", host));
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => {
+                let _ = stream.set_read_timeout(Some(timeout));
+                let _ = stream.set_write_timeout(Some(timeout));
+                return Ok(stream);
+            }
+            Err(e) => last_error = CheckFailure::new(classify_io_error(&e), format!("{}: {}", addr, e)),
+        }
+    }
+    Err(last_error)
+}
+
+fn io_failure(e: std::io::Error) -> CheckFailure {
+    CheckFailure::new(classify_io_error(&e), e.to_string())
+}
+
+/// Read until `done` says the buffer is complete, the peer closes, or `limit` bytes arrive
+fn read_until(stream: &mut impl Read, limit: usize, done: impl Fn(&[u8]) -> bool) -> Result<Vec<u8>, CheckFailure> {
+    let mut buf = vec![];
+    let mut chunk = [0u8; 512];
+    while buf.len() < limit && !done(&buf) {
+        let n = stream.read(&mut chunk).map_err(io_failure)?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    if buf.is_empty() {
+        return Err(CheckFailure::new(FailureCategory::Other, "Connection closed without a reply"));
+    }
+    Ok(buf)
+}
+
+#[cfg(feature = "native-tls")]
+fn tls_connect(
+    stream: TcpStream,
+    host: &str,
+    accept_invalid: bool,
+) -> Result<native_tls::TlsStream<TcpStream>, CheckFailure> {
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(accept_invalid)
+        .build()
+        .map_err(|e| CheckFailure::new(FailureCategory::Tls, e.to_string()))?;
+    connector
+        .connect(host, stream)
+        .map_err(|e| CheckFailure::new(FailureCategory::Tls, e.to_string()))
+}
+
+fn websocket_handshake(stream: &mut (impl Read + Write), host: &str, path: &str) -> Result<String, CheckFailure> {
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        path, host, WEBSOCKET_KEY
+    );
+    stream.write_all(request.as_bytes()).map_err(io_failure)?;
+    let reply = read_until(stream, 8192, |buf| buf.windows(4).any(|w| w == b"\r\n\r\n"))?;
+    parse_upgrade_reply(&reply)
+}
+
+fn parse_upgrade_reply(reply: &[u8]) -> Result<String, CheckFailure> {
+    let reply = String::from_utf8_lossy(reply);
+    let status_line = reply.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("101") {
+        return Err(CheckFailure::new(
+            FailureCategory::BadStatus,
+            format!("Upgrade refused: {}", status_line.trim()),
+        ));
+    }
+    Ok("WebSocket upgrade accepted".to_string())
+}
+
+fn probe_websocket(host: &str, port: u16, path: &str, tls: bool, timeout: Duration) -> Result<String, CheckFailure> {
+    let mut stream = connect(host, port, timeout)?;
+    if !tls {
+        return websocket_handshake(&mut stream, host, path);
+    }
+
+    #[cfg(feature = "native-tls")]
+    {
+        let mut stream = tls_connect(stream, host, false)?;
+        websocket_handshake(&mut stream, host, path)
+    }
+
+    #[cfg(not(feature = "native-tls"))]
+    {
+        let _ = stream;
+        Err(CheckFailure::new(FailureCategory::InvalidConfig, "wss:// probes need TLS support"))
+    }
+}
+
+/// Encode a command as a RESP array of bulk strings
+fn resp_command(args: &[&str]) -> Vec<u8> {
+    let mut out = format!("*{}\r\n", args.len());
+    for arg in args {
+        out.push_str(&format!("${}\r\n{}\r\n", arg.len(), arg));
+    }
+    out.into_bytes()
+}
+
+fn read_resp_line(stream: &mut impl Read) -> Result<String, CheckFailure> {
+    let reply = read_until(stream, 1024, |buf| buf.ends_with(b"\r\n"))?;
+    Ok(String::from_utf8_lossy(&reply).trim_end().to_string())
+}
+
+/// Returns whether the server is still loading its dataset
+fn probe_redis(host: &str, port: u16, password: Option<&str>, timeout: Duration) -> Result<(String, bool), CheckFailure> {
+    let mut stream = connect(host, port, timeout)?;
+
+    if let Some(password) = password {
+        stream.write_all(&resp_command(&["AUTH", password])).map_err(io_failure)?;
+        let reply = read_resp_line(&mut stream)?;
+        if !reply.starts_with("+OK") {
+            return Err(CheckFailure::new(FailureCategory::AssertionFailed, format!("AUTH failed: {}", reply)));
+        }
+    }
+
+    stream.write_all(&resp_command(&["PING"])).map_err(io_failure)?;
+    parse_ping_reply(&read_resp_line(&mut stream)?)
+}
+
+fn parse_ping_reply(reply: &str) -> Result<(String, bool), CheckFailure> {
+    match reply {
+        "+PONG" => Ok(("PONG".to_string(), false)),
+        r if r.starts_with("-LOADING") => Ok(("Redis is loading its dataset".to_string(), true)),
+        r => Err(CheckFailure::new(
+            FailureCategory::AssertionFailed,
+            format!("Unexpected reply to PING: {}", r),
+        )),
+    }
+}
+
+/// Send an SSLRequest; any server answers with a single 'S' or 'N' byte
+fn probe_postgres(host: &str, port: u16, timeout: Duration) -> Result<String, CheckFailure> {
+    const SSL_REQUEST: [u8; 8] = [0, 0, 0, 8, 0x04, 0xd2, 0x16, 0x2f];
+
+    let mut stream = connect(host, port, timeout)?;
+    stream.write_all(&SSL_REQUEST).map_err(io_failure)?;
+    let reply = read_until(&mut stream, 1, |buf| !buf.is_empty())?;
+    parse_postgres_reply(reply[0])
+}
+
+fn parse_postgres_reply(reply: u8) -> Result<String, CheckFailure> {
+    match reply {
+        b'S' => Ok("Postgres is accepting connections (SSL available)".to_string()),
+        b'N' => Ok("Postgres is accepting connections".to_string()),
+        b'E' => Err(CheckFailure::new(FailureCategory::AssertionFailed, "Postgres replied with an error")),
+        other => Err(CheckFailure::new(
+            FailureCategory::AssertionFailed,
+            format!("Unexpected reply byte 0x{:02x}; not a Postgres server?", other),
+        )),
+    }
+}
+
+/// The server speaks first: a handshake packet with the protocol and server version
+fn probe_mysql(host: &str, port: u16, timeout: Duration) -> Result<String, CheckFailure> {
+    let mut stream = connect(host, port, timeout)?;
+    let packet = read_until(&mut stream, 4096, mysql_packet_complete)?;
+    parse_mysql_greeting(&packet)
+}
+
+/// A packet starts with a 3-byte little-endian payload length and a sequence byte
+fn mysql_packet_complete(buf: &[u8]) -> bool {
+    buf.len() >= 4 && buf.len() >= 4 + (buf[0] as usize | (buf[1] as usize) << 8 | (buf[2] as usize) << 16)
+}
+
+fn parse_mysql_greeting(packet: &[u8]) -> Result<String, CheckFailure> {
+    let payload = packet.get(4..).unwrap_or_default();
+
+    match payload.first() {
+        Some(0x0a) => {
+            let version = payload[1..].split(|b| *b == 0).next().unwrap_or_default();
+            Ok(format!("MySQL {}", String::from_utf8_lossy(version)))
+        }
+        // ERR packet: 0xff, 2-byte error code, then the message (optionally with a SQL state)
+        Some(0xff) => {
+            let message = payload.get(3..).unwrap_or_default();
+            let message = String::from_utf8_lossy(message);
+            Err(CheckFailure::new(
+                FailureCategory::AssertionFailed,
+                format!("MySQL refused the connection: {}", message.trim_start_matches('#')),
+            ))
+        }
+        _ => Err(CheckFailure::new(FailureCategory::AssertionFailed, "Not a MySQL server greeting")),
+    }
+}
+
+/// Split one DER element into (tag, contents, rest)
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count].iter().fold(0usize, |acc, b| acc << 8 | *b as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        return None;
+    }
+    Some((tag, &rest[..len], &rest[len..]))
+}
+
+/// Read `notAfter` from a DER-encoded X.509 certificate
+fn certificate_not_after(der: &[u8]) -> Option<chrono::DateTime<chrono::Utc>> {
+    let (_, certificate, _) = der_element(der)?;
+    let (_, tbs, _) = der_element(certificate)?;
+
+    let mut fields = tbs;
+    // Optional explicit [0] version
+    if fields.first() == Some(&0xa0) {
+        fields = der_element(fields)?.2;
+    }
+    // serialNumber, signature, issuer
+    for _ in 0..3 {
+        fields = der_element(fields)?.2;
+    }
+    let (_, validity, _) = der_element(fields)?;
+    // Skip notBefore
+    let (_, _, rest) = der_element(validity)?;
+    let (tag, not_after, _) = der_element(rest)?;
+
+    let text = std::str::from_utf8(not_after).ok()?;
+    let naive = match tag {
+        // UTCTime: YYMMDDHHMMSSZ
+        0x17 => chrono::NaiveDateTime::parse_from_str(text, "%y%m%d%H%M%SZ").ok()?,
+        // GeneralizedTime: YYYYMMDDHHMMSSZ
+        0x18 => chrono::NaiveDateTime::parse_from_str(text, "%Y%m%d%H%M%SZ").ok()?,
+        _ => return None,
+    };
+    Some(naive.and_utc())
+}
+
+/// Returns the message and whether the certificate is close to expiry
+fn probe_tls_cert(host: &str, port: u16, warn_days: u32, timeout: Duration) -> Result<(String, bool), CheckFailure> {
+    #[cfg(feature = "native-tls")]
+    {
+        let stream = connect(host, port, timeout)?;
+        // Accept invalid certificates so an expired one can still be inspected and reported
+        let stream = tls_connect(stream, host, true)?;
+        let certificate = stream
+            .peer_certificate()
+            .map_err(|e| CheckFailure::new(FailureCategory::Tls, e.to_string()))?
+            .ok_or_else(|| CheckFailure::new(FailureCategory::Tls, "Server sent no certificate"))?;
+        let der = certificate
+            .to_der()
+            .map_err(|e| CheckFailure::new(FailureCategory::Tls, e.to_string()))?;
+        let not_after = certificate_not_after(&der)
+            .ok_or_else(|| CheckFailure::new(FailureCategory::Tls, "Could not read certificate expiry"))?;
+
+        let days_left = (not_after - chrono::Utc::now()).num_days();
+        let expiry = not_after.format("%Y-%m-%d");
+        if not_after <= chrono::Utc::now() {
+            return Err(CheckFailure::new(
+                FailureCategory::Tls,
+                format!("Certificate expired on {}", expiry),
+            ));
+        }
+        Ok((
+            format!("Certificate valid until {} ({} days left)", expiry, days_left),
+            days_left < i64::from(warn_days),
+        ))
+    }
+
+    #[cfg(not(feature = "native-tls"))]
+    {
+        let _ = (host, port, warn_days, timeout, certificate_not_after);
+        Err(CheckFailure::new(FailureCategory::InvalidConfig, "TLS probes need TLS support"))
+    }
+}
+
+/// Run a service's protocol probe
+pub(crate) fn run_probe(spec: &ProbeSpec, agent: &LaunchAgent) -> HealthResult {
+    let start = Instant::now();
+    let latency = |start: Instant| start.elapsed().as_millis() as u64;
+    let (host, port) = match spec.target(agent) {
+        Ok(target) => target,
+        Err(failure) => return HealthResult::unhealthy(latency(start), None, failure),
+    };
+    let timeout = spec.timeout();
+
+    // (message, degraded)
+    let outcome = match &spec.kind {
+        ProbeKind::WebSocket { path, tls } => probe_websocket(&host, port, path, *tls, timeout).map(|m| (m, false)),
+        ProbeKind::Redis { password } => probe_redis(&host, port, password.as_deref(), timeout),
+        ProbeKind::Postgres => probe_postgres(&host, port, timeout).map(|m| (m, false)),
+        ProbeKind::Mysql => probe_mysql(&host, port, timeout).map(|m| (m, false)),
+        ProbeKind::TlsCert { warn_days } => {
+            probe_tls_cert(&host, port, warn_days.unwrap_or(DEFAULT_WARN_DAYS), timeout)
+        }
+    };
+
+    match outcome {
+        Ok((message, degraded)) => {
+            let mut result = HealthResult::healthy(latency(start), None, message);
+            if degraded {
+                result.status = HealthStatus::Degraded;
+            }
+            result
+        }
+        Err(failure) => HealthResult::unhealthy(latency(start), None, failure),
+    }
+}
+
+/// Run a probe against a service, using its configured probe unless one is given
+#[tauri::command(async)]
+pub fn check_probe(label: String, spec: Option<ProbeSpec>) -> Result<HealthResult, String> {
+    let service = crate::find_service(&label)?;
+    let spec = spec
+        .or_else(|| service.probe.clone())
+        .ok_or_else(|| format!("No probe configured for {}", label))?;
+    Ok(run_probe(&spec, &service))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Replays a canned reply and records what the probe sent
+    struct Scripted {
+        reply: Cursor<Vec<u8>>,
+        sent: Vec<u8>,
+    }
+
+    impl Scripted {
+        fn new(reply: &[u8]) -> Self {
+            Self {
+                reply: Cursor::new(reply.to_vec()),
+                sent: vec![],
+            }
+        }
+    }
+
+    impl Read for Scripted {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.reply.read(buf)
+        }
+    }
+
+    impl Write for Scripted {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.sent.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        if contents.len() < 0x80 {
+            out.push(contents.len() as u8);
+        } else {
+            out.extend_from_slice(&[0x82, (contents.len() >> 8) as u8, contents.len() as u8]);
+        }
+        out.extend_from_slice(contents);
+        out
+    }
+
+    /// A certificate with just enough structure to reach `notAfter`
+    fn certificate(not_after: Vec<u8>, with_version: bool) -> Vec<u8> {
+        let mut tbs = vec![];
+        if with_version {
+            tbs.extend(der(0xa0, &der(0x02, &[2])));
+        }
+        tbs.extend(der(0x02, &[0x01, 0x23]));
+        tbs.extend(der(0x30, &der(0x06, &[0x2a, 0x86])));
+        tbs.extend(der(0x30, &[b'x'; 200]));
+        let mut validity = der(0x17, b"240101000000Z");
+        validity.extend(not_after);
+        tbs.extend(der(0x30, &validity));
+        der(0x30, &der(0x30, &tbs))
+    }
+
+    #[test]
+    fn resp_commands_are_bulk_string_arrays() {
+        assert_eq!(resp_command(&["PING"]), b"*1\r\n$4\r\nPING\r\n");
+        assert_eq!(resp_command(&["AUTH", "pässword"]), "*2\r\n$4\r\nAUTH\r\n$9\r\npässword\r\n".as_bytes());
+    }
+
+    #[test]
+    fn redis_ping_replies() {
+        assert_eq!(parse_ping_reply("+PONG").ok(), Some(("PONG".to_string(), false)));
+        assert_eq!(parse_ping_reply("-LOADING Redis is loading").ok().map(|(_, loading)| loading), Some(true));
+        let failure = parse_ping_reply("-NOAUTH Authentication required.").err().unwrap();
+        assert_eq!(failure.category, FailureCategory::AssertionFailed);
+    }
+
+    #[test]
+    fn read_resp_line_trims_the_terminator() {
+        assert_eq!(read_resp_line(&mut Scripted::new(b"+PONG\r\n")).ok().as_deref(), Some("+PONG"));
+        let failure = read_resp_line(&mut Scripted::new(b"")).err().unwrap();
+        assert_eq!(failure.message, "Connection closed without a reply");
+    }
+
+    #[test]
+    fn postgres_replies() {
+        assert!(parse_postgres_reply(b'S').is_ok());
+        assert!(parse_postgres_reply(b'N').is_ok());
+        assert!(parse_postgres_reply(b'E').is_err());
+        let failure = parse_postgres_reply(b'H').err().unwrap();
+        assert!(failure.message.contains("0x48"));
+    }
+
+    #[test]
+    fn mysql_packets() {
+        let greeting = b"\x0e\x00\x00\x00\x0a8.0.36\x00\x01\x02\x03\x04\x05\x06";
+        assert!(mysql_packet_complete(greeting));
+        assert!(!mysql_packet_complete(&greeting[..8]));
+        assert!(!mysql_packet_complete(b"\x0e\x00"));
+        assert_eq!(parse_mysql_greeting(greeting).ok().as_deref(), Some("MySQL 8.0.36"));
+
+        let refused = b"\x17\x00\x00\x00\xff\x6a\x04#HY000Host not allowed";
+        let failure = parse_mysql_greeting(refused).err().unwrap();
+        assert_eq!(failure.message, "MySQL refused the connection: HY000Host not allowed");
+
+        assert!(parse_mysql_greeting(b"SSH-2.0-OpenSSH_9.6\r\n").is_err());
+        assert!(parse_mysql_greeting(b"\x00\x00").is_err());
+    }
+
+    #[test]
+    fn websocket_upgrade() {
+        let mut stream = Scripted::new(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n");
+        assert!(websocket_handshake(&mut stream, "localhost", "/ws").is_ok());
+        let sent = String::from_utf8(stream.sent).unwrap();
+        assert!(sent.starts_with("GET /ws HTTP/1.1\r\nHost: localhost\r\n"));
+        assert!(sent.ends_with("\r\n\r\n"));
+
+        let failure = parse_upgrade_reply(b"HTTP/1.1 400 Bad Request\r\n\r\n").err().unwrap();
+        assert_eq!(failure.category, FailureCategory::BadStatus);
+        assert_eq!(failure.message, "Upgrade refused: HTTP/1.1 400 Bad Request");
+        assert!(parse_upgrade_reply(b"").is_err());
+    }
+
+    #[test]
+    fn der_elements() {
+        assert_eq!(der_element(&[0x02, 0x01, 0x05, 0xff]), Some((0x02, &[0x05][..], &[0xff][..])));
+        let long = der(0x04, &[7; 300]);
+        let (tag, contents, rest) = der_element(&long).unwrap();
+        assert_eq!((tag, contents.len(), rest.len()), (0x04, 300, 0));
+
+        // Truncated contents, missing length bytes and indefinite lengths
+        assert_eq!(der_element(&[0x02, 0x03, 0x05]), None);
+        assert_eq!(der_element(&[0x30, 0x82, 0x01]), None);
+        assert_eq!(der_element(&[0x30, 0x80]), None);
+        assert_eq!(der_element(&[]), None);
+    }
+
+    #[test]
+    fn certificate_expiry() {
+        let not_after = certificate_not_after(&certificate(der(0x17, b"301231235959Z"), true)).unwrap();
+        assert_eq!(not_after.to_rfc3339(), "2030-12-31T23:59:59+00:00");
+
+        let not_after = certificate_not_after(&certificate(der(0x18, b"20550601120000Z"), false)).unwrap();
+        assert_eq!(not_after.to_rfc3339(), "2055-06-01T12:00:00+00:00");
+    }
+
+    #[test]
+    fn malformed_certificates_have_no_expiry() {
+        assert_eq!(certificate_not_after(&certificate(der(0x0c, b"301231235959Z"), true)), None);
+        assert_eq!(certificate_not_after(&certificate(der(0x17, b"not a date"), true)), None);
+        let mut truncated = certificate(der(0x17, b"301231235959Z"), true);
+        truncated.truncate(40);
+        assert_eq!(certificate_not_after(&truncated), None);
+    }
+}
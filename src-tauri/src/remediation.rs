@@ -0,0 +1,337 @@
+//! Self-healing for hung services.
+//!
+//! KeepAlive only helps once a process exits; a process that is alive but no
+//! longer answers stays "running" forever. With a `RemediationPolicy`, a service
+//! that fails enough health checks in a row is restarted, limited to a number of
+//! restarts per window and a cooldown between them. Every decision is written to
+//! `remediation_log.json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::crash_loop;
+use crate::monitor::ServiceEvent;
+use crate::{find_service, get_metadata_file_path, load_all_metadata, restart_service, unix_now};
+
+/// Log entries kept
+const LOG_LIMIT: usize = 200;
+
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Per-service remediation policy stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemediationPolicy {
+    /// Consecutive failed health checks before restarting
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Restarts allowed within the window before giving up
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u32,
+    /// Minimum time between two restarts, so the service can come up
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_window_minutes() -> u32 {
+    30
+}
+
+fn default_cooldown_secs() -> u64 {
+    120
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemediationAction {
+    Restarted,
+    RestartFailed,
+    /// The restart budget for the window is used up
+    RateLimited,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RemediationRecord {
+    pub label: String,
+    /// Unix timestamp (seconds)
+    pub at: u64,
+    pub action: RemediationAction,
+    pub consecutive_failures: u32,
+    /// Message of the failed check that triggered this
+    pub reason: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Default)]
+struct Track {
+    restarts: VecDeque<u64>,
+    /// A restart is running on a background thread
+    in_progress: bool,
+    /// Rate limiting was already logged for the current window
+    limited: bool,
+    /// The monitor's failure count when we last restarted. A restart that keeps the
+    /// service running doesn't reset that count, so only failures past it count.
+    failures_at_restart: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Decision {
+    Wait,
+    /// Over the restart budget; returned once per window
+    RateLimited,
+    Restart,
+}
+
+impl Track {
+    fn decide(&mut self, policy: &RemediationPolicy, consecutive_failures: u32, now: u64) -> Decision {
+        // A lower count means the service recovered since our restart
+        if consecutive_failures <= self.failures_at_restart {
+            self.failures_at_restart = 0;
+        }
+        if consecutive_failures - self.failures_at_restart < policy.failure_threshold.max(1) {
+            return Decision::Wait;
+        }
+
+        let window_secs = u64::from(policy.window_minutes) * 60;
+        while self.restarts.front().is_some_and(|t| now.saturating_sub(*t) > window_secs) {
+            self.restarts.pop_front();
+        }
+        if self.in_progress
+            || self
+                .restarts
+                .back()
+                .is_some_and(|t| now.saturating_sub(*t) < policy.cooldown_secs)
+        {
+            return Decision::Wait;
+        }
+        if self.restarts.len() >= policy.max_restarts as usize {
+            if self.limited {
+                return Decision::Wait;
+            }
+            self.limited = true;
+            return Decision::RateLimited;
+        }
+        self.limited = false;
+        self.in_progress = true;
+        self.restarts.push_back(now);
+        self.failures_at_restart = consecutive_failures;
+        Decision::Restart
+    }
+}
+
+/// Managed state with restart history per service
+#[derive(Default)]
+pub struct Remediator {
+    tracks: Mutex<HashMap<String, Track>>,
+}
+
+fn get_log_file_path() -> PathBuf {
+    get_metadata_file_path().with_file_name("remediation_log.json")
+}
+
+fn load_log() -> Vec<RemediationRecord> {
+    let path = get_log_file_path();
+    if path.exists() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(records) = serde_json::from_str(&content) {
+                return records;
+            }
+        }
+    }
+    vec![]
+}
+
+fn write_log(record: RemediationRecord) -> Result<(), String> {
+    let _guard = LOG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut records = load_log();
+    records.push(record);
+    if records.len() > LOG_LIMIT {
+        let excess = records.len() - LOG_LIMIT;
+        records.drain(..excess);
+    }
+
+    let path = get_log_file_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Record a remediation and tell the frontend about it
+fn append_log(app: &AppHandle, record: RemediationRecord) {
+    let _ = app.emit("service-remediation", &record);
+    if let Err(e) = write_log(record) {
+        eprintln!("Failed to write remediation log: {}", e);
+    }
+}
+
+/// React to failed health checks according to the service's policy
+pub(crate) fn handle_event(app: &AppHandle, event: &ServiceEvent) {
+    let ServiceEvent::CheckFailed {
+        label,
+        consecutive_failures,
+        result,
+    } = event
+    else {
+        return;
+    };
+    let Some(policy) = load_all_metadata().remove(label).and_then(|m| m.remediation) else {
+        return;
+    };
+
+    let now = unix_now();
+    let record = |action, detail| RemediationRecord {
+        label: label.clone(),
+        at: now,
+        action,
+        consecutive_failures: *consecutive_failures,
+        reason: result.message.clone(),
+        detail,
+    };
+
+    {
+        let remediator = app.state::<Remediator>();
+        let Ok(mut tracks) = remediator.tracks.lock() else { return };
+        let track = tracks.entry(label.clone()).or_default();
+        match track.decide(&policy, *consecutive_failures, now) {
+            Decision::Wait => return,
+            Decision::RateLimited => {
+                let detail = format!(
+                    "{} restarts in {} min; leaving the service alone",
+                    track.restarts.len(),
+                    policy.window_minutes
+                );
+                drop(tracks);
+                append_log(app, record(RemediationAction::RateLimited, Some(detail)));
+                return;
+            }
+            Decision::Restart => {}
+        }
+    }
+
+    // Restarting waits on launchctl; keep it off the monitor thread
+    let app = app.clone();
+    let (label, record) = (label.clone(), record(RemediationAction::Restarted, None));
+    std::thread::spawn(move || {
+        let outcome = find_service(&label).and_then(|service| {
+            // Registered up front: starting dependencies can take a while, and the
+            // restart must not show up as a crash or count toward the crash-loop policy
+            crash_loop::expect_restart(&service.file_path);
+            restart_service(service.file_path)
+        });
+        let record = match outcome {
+            Ok(message) => RemediationRecord {
+                detail: Some(message),
+                ..record
+            },
+            Err(e) => RemediationRecord {
+                action: RemediationAction::RestartFailed,
+                detail: Some(e),
+                ..record
+            },
+        };
+        append_log(&app, record);
+
+        let remediator = app.state::<Remediator>();
+        if let Ok(mut tracks) = remediator.tracks.lock() {
+            if let Some(track) = tracks.get_mut(&label) {
+                track.in_progress = false;
+            }
+        };
+    });
+}
+
+/// Remediation log, newest first, optionally for a single service
+#[tauri::command]
+pub fn get_remediation_log(label: Option<String>) -> Result<Vec<RemediationRecord>, String> {
+    let mut records: Vec<RemediationRecord> = load_log()
+        .into_iter()
+        .filter(|r| label.as_ref().is_none_or(|l| &r.label == l))
+        .collect();
+    records.reverse();
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RemediationPolicy {
+        RemediationPolicy {
+            failure_threshold: 3,
+            max_restarts: 2,
+            window_minutes: 10,
+            cooldown_secs: 60,
+        }
+    }
+
+    /// Track after a finished restart at `at` with the monitor's count at `failures`
+    fn restarted(track: &mut Track, failures: u32, at: u64) {
+        assert_eq!(track.decide(&policy(), failures, at), Decision::Restart);
+        track.in_progress = false;
+    }
+
+    #[test]
+    fn waits_for_the_threshold() {
+        let mut track = Track::default();
+        assert_eq!(track.decide(&policy(), 1, 0), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 2, 10), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 3, 20), Decision::Restart);
+        // Still restarting
+        assert_eq!(track.decide(&policy(), 4, 30), Decision::Wait);
+    }
+
+    #[test]
+    fn counts_only_failures_since_the_last_restart() {
+        let mut track = Track::default();
+        restarted(&mut track, 3, 0);
+        // Past the cooldown, but only one new failure
+        assert_eq!(track.decide(&policy(), 4, 100), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 5, 110), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 6, 120), Decision::Restart);
+    }
+
+    #[test]
+    fn recovery_resets_the_baseline() {
+        let mut track = Track::default();
+        restarted(&mut track, 5, 0);
+        // The service recovered, then failed again from one
+        assert_eq!(track.decide(&policy(), 1, 100), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 2, 110), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 3, 120), Decision::Restart);
+    }
+
+    #[test]
+    fn respects_the_cooldown() {
+        let mut track = Track::default();
+        restarted(&mut track, 3, 0);
+        assert_eq!(track.decide(&policy(), 6, 59), Decision::Wait);
+        assert_eq!(track.decide(&policy(), 7, 60), Decision::Restart);
+    }
+
+    #[test]
+    fn rate_limits_within_the_window_once() {
+        let mut track = Track::default();
+        restarted(&mut track, 3, 0);
+        restarted(&mut track, 6, 100);
+        assert_eq!(track.decide(&policy(), 9, 200), Decision::RateLimited);
+        assert_eq!(track.decide(&policy(), 10, 300), Decision::Wait);
+        // The first restart leaves the ten-minute window
+        assert_eq!(track.decide(&policy(), 11, 601), Decision::Restart);
+    }
+}
@@ -0,0 +1,280 @@
+//! Log rotation and retention.
+//!
+//! launchd never rotates `StandardOutPath`/`StandardErrorPath`, so services with a
+//! `LogRotationPolicy` are checked on a timer. Rotation is copy-truncate: the
+//! current content is copied to `<file>.1`, older archives shift up to
+//! `<file>.<keep>`, and the original is truncated in place; gzipping runs on the
+//! copy after the truncate. launchd opens log files for appending, so the service
+//! keeps writing to the same file without being restarted; lines written between
+//! the copy and the truncate are lost, which is the usual price of copy-truncate.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+use crate::logs::{log_paths, rotated_files, LogStream};
+use crate::{collect_services, find_service, load_all_metadata, LaunchAgent};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps the timer and manual rotations from shifting the same archives at once
+static ROTATION_LOCK: Mutex<()> = Mutex::new(());
+
+/// Per-service rotation policy stored in `ServiceMetadata`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogRotationPolicy {
+    /// Rotate once a log file reaches this size
+    pub max_size_mb: Option<u64>,
+    /// Rotate once the current file has been written to for this long
+    pub max_age_days: Option<u32>,
+    /// Archives kept per log file; older ones are deleted
+    #[serde(default = "default_keep")]
+    pub keep: u32,
+    /// Gzip archives
+    #[serde(default = "default_compress")]
+    pub compress: bool,
+}
+
+fn default_keep() -> u32 {
+    5
+}
+
+fn default_compress() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogFileUsage {
+    pub stream: LogStream,
+    pub path: String,
+    pub current_bytes: u64,
+    pub archived_bytes: u64,
+    pub archives: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogDiskUsage {
+    pub label: String,
+    pub total_bytes: u64,
+    pub files: Vec<LogFileUsage>,
+}
+
+/// Payload of the `service-logs-rotated` event
+#[derive(Debug, Serialize, Clone)]
+pub struct RotationEvent {
+    pub label: String,
+    pub path: String,
+    pub archive: String,
+    pub bytes: u64,
+}
+
+fn archive_path(path: &str, index: u32, compress: bool) -> PathBuf {
+    let suffix = if compress { ".gz" } else { "" };
+    PathBuf::from(format!("{}.{}{}", path, index, suffix))
+}
+
+/// Existing numbered archive `index`, compressed or not
+fn existing_archive(path: &str, index: u32) -> Option<PathBuf> {
+    [archive_path(path, index, true), archive_path(path, index, false)]
+        .into_iter()
+        .find(|p| p.exists())
+}
+
+/// When the current file started collecting output: the last rotation, or the file's creation
+fn current_since(path: &str) -> Option<SystemTime> {
+    if let Some(newest) = existing_archive(path, 1) {
+        return fs::metadata(newest).and_then(|m| m.modified()).ok();
+    }
+    let meta = fs::metadata(path).ok()?;
+    meta.created().or_else(|_| meta.modified()).ok()
+}
+
+fn is_due(path: &str, policy: &LogRotationPolicy) -> bool {
+    let Ok(meta) = fs::metadata(path) else { return false };
+    if meta.len() == 0 {
+        return false;
+    }
+    if policy.max_size_mb.is_some_and(|mb| meta.len() >= mb * 1024 * 1024) {
+        return true;
+    }
+    policy.max_age_days.is_some_and(|days| {
+        current_since(path)
+            .and_then(|since| since.elapsed().ok())
+            .is_some_and(|age| age >= Duration::from_secs(u64::from(days) * 86400))
+    })
+}
+
+/// Shift archives up by one, dropping those beyond `keep`
+fn shift_archives(path: &str, keep: u32) -> Result<(), String> {
+    let mut index = keep.max(1);
+    while let Some(oldest) = existing_archive(path, index) {
+        fs::remove_file(oldest).map_err(|e| e.to_string())?;
+        index += 1;
+    }
+    for index in (1..keep.max(1)).rev() {
+        if let Some(archive) = existing_archive(path, index) {
+            let compressed = archive.extension().is_some_and(|e| e == "gz");
+            fs::rename(&archive, archive_path(path, index + 1, compressed)).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy-truncate one log file; returns the archive path and the bytes archived
+fn rotate_file(path: &str, policy: &LogRotationPolicy) -> Result<(PathBuf, u64), String> {
+    let _guard = ROTATION_LOCK.lock().map_err(|e| e.to_string())?;
+    shift_archives(path, policy.keep)?;
+
+    // Plain copy then truncate right away, so the window in which new lines are lost
+    // stays short; compressing happens afterwards from the copy
+    let archive = archive_path(path, 1, policy.compress);
+    let copy = if policy.compress { PathBuf::from(format!("{}.1.tmp", path)) } else { archive.clone() };
+    let bytes = io::copy(
+        &mut File::open(path).map_err(|e| e.to_string())?,
+        &mut File::create(&copy).map_err(|e| e.to_string())?,
+    )
+    .map_err(|e| e.to_string())?;
+    OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_len(0))
+        .map_err(|e| e.to_string())?;
+
+    if policy.compress {
+        if let Err(e) = compress_file(&copy, &archive) {
+            // The content is already out of the log file: keep it as a plain archive
+            eprintln!("Failed to compress {}: {}", copy.display(), e);
+            let _ = fs::remove_file(&archive);
+            let plain = archive_path(path, 1, false);
+            fs::rename(&copy, &plain).map_err(|e| e.to_string())?;
+            return Ok((plain, bytes));
+        }
+        fs::remove_file(&copy).map_err(|e| e.to_string())?;
+    }
+    Ok((archive, bytes))
+}
+
+fn compress_file(source: &Path, target: &Path) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(target)?, Compression::default());
+    io::copy(&mut File::open(source)?, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+fn rotate_service(app: &AppHandle, service: &LaunchAgent, policy: &LogRotationPolicy, force: bool) -> Vec<String> {
+    let mut errors = vec![];
+    for (_, path) in log_paths(service) {
+        let due = if force {
+            fs::metadata(&path).is_ok_and(|m| m.len() > 0)
+        } else {
+            is_due(&path, policy)
+        };
+        if !due {
+            continue;
+        }
+        match rotate_file(&path, policy) {
+            Ok((archive, bytes)) => {
+                let event = RotationEvent {
+                    label: service.label.clone(),
+                    path: path.clone(),
+                    archive: archive.to_string_lossy().into_owned(),
+                    bytes,
+                };
+                let _ = app.emit("service-logs-rotated", &event);
+            }
+            Err(e) => errors.push(format!("{}: {}", path, e)),
+        }
+    }
+    errors
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        tick(&app);
+        std::thread::sleep(TICK_INTERVAL);
+    });
+}
+
+fn tick(app: &AppHandle) {
+    let metadata = load_all_metadata();
+    if metadata.values().all(|m| m.log_rotation.is_none()) {
+        return;
+    }
+    let Ok(services) = collect_services() else { return };
+    for service in services {
+        let Some(policy) = metadata.get(&service.label).and_then(|m| m.log_rotation.as_ref()) else {
+            continue;
+        };
+        for error in rotate_service(app, &service, policy, false) {
+            eprintln!("Failed to rotate logs of {}: {}", service.label, error);
+        }
+    }
+}
+
+/// Rotate a service's logs now, using its policy or the defaults
+#[tauri::command(async)]
+pub fn rotate_service_logs(app: AppHandle, label: String) -> Result<String, String> {
+    let service = find_service(&label)?;
+    let policy = load_all_metadata()
+        .remove(&label)
+        .and_then(|m| m.log_rotation)
+        .unwrap_or(LogRotationPolicy {
+            max_size_mb: None,
+            max_age_days: None,
+            keep: default_keep(),
+            compress: default_compress(),
+        });
+    let errors = rotate_service(&app, &service, &policy, true);
+    if errors.is_empty() {
+        Ok(format!("Rotated logs of {}", label))
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |m| m.len())
+}
+
+fn disk_usage(service: &LaunchAgent) -> LogDiskUsage {
+    let files: Vec<LogFileUsage> = log_paths(service)
+        .into_iter()
+        .map(|(stream, path)| {
+            let archives = rotated_files(&path);
+            LogFileUsage {
+                stream,
+                current_bytes: file_size(Path::new(&path)),
+                archived_bytes: archives.iter().map(|a| file_size(a)).sum(),
+                archives: archives.iter().map(|a| a.to_string_lossy().into_owned()).collect(),
+                path,
+            }
+        })
+        .collect();
+    LogDiskUsage {
+        label: service.label.clone(),
+        total_bytes: files.iter().map(|f| f.current_bytes + f.archived_bytes).sum(),
+        files,
+    }
+}
+
+/// Disk space taken by log files and their archives, per service, largest first
+#[tauri::command(async)]
+pub fn get_log_disk_usage(label: Option<String>) -> Result<Vec<LogDiskUsage>, String> {
+    let services = match label {
+        Some(label) => vec![find_service(&label)?],
+        None => collect_services()?,
+    };
+    let mut usage: Vec<LogDiskUsage> = services
+        .iter()
+        .filter(|s| !log_paths(s).is_empty())
+        .map(disk_usage)
+        .collect();
+    usage.sort_by_key(|u| std::cmp::Reverse(u.total_bytes));
+    Ok(usage)
+}
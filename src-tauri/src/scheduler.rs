@@ -0,0 +1,466 @@
+//! Scheduled service actions.
+//!
+//! Rules live in `schedules.json` next to `metadata.json` and use five-field cron
+//! expressions (`minute hour day-of-month month day-of-week`) evaluated in local
+//! time, e.g. `0 4 * * *` for "daily at 04:00" or `0 19 * * 1-5` for "weekdays at
+//! 19:00". A background thread fires due rules while the app runs, including when
+//! only the tray icon is visible.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::bulk::{self, ServiceAction};
+use crate::{get_metadata_file_path, tray, unix_now};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Serializes read-modify-write cycles on `schedules.json` between commands and the scheduler thread
+static SCHEDULES_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl From<ScheduleAction> for ServiceAction {
+    fn from(action: ScheduleAction) -> Self {
+        match action {
+            ScheduleAction::Start => ServiceAction::Start,
+            ScheduleAction::Stop => ServiceAction::Stop,
+            ScheduleAction::Restart => ServiceAction::Restart,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScheduleRule {
+    /// Assigned on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub label: String,
+    pub action: ScheduleAction,
+    pub cron: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Unix timestamp (seconds) of the last execution
+    pub last_run: Option<u64>,
+    pub last_result: Option<String>,
+    /// Unix timestamp (seconds) of the next execution; None while paused
+    pub next_run: Option<u64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Payload of the `schedule-executed` event
+#[derive(Debug, Serialize, Clone)]
+struct ScheduleExecuted {
+    id: String,
+    label: String,
+    action: ScheduleAction,
+    success: bool,
+    message: String,
+}
+
+/// A parsed five-field cron expression
+struct CronSchedule {
+    minutes: [bool; 60],
+    hours: [bool; 24],
+    days: [bool; 32],
+    months: [bool; 13],
+    weekdays: [bool; 7],
+    /// Standard cron rule: when both day fields are restricted, either may match
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+fn parse_value(value: &str, names: &[&str], offset: u32) -> Option<u32> {
+    value.parse().ok().or_else(|| {
+        let lower = value.to_lowercase();
+        names.iter().position(|n| *n == lower).map(|i| i as u32 + offset)
+    })
+}
+
+/// Parse one cron field into a mask indexed by value
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], name_offset: u32) -> Result<Vec<bool>, String> {
+    let mut mask = vec![false; max as usize + 1];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("Invalid step '{}'", step))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let a = parse_value(a, names, name_offset).ok_or_else(|| format!("Invalid value '{}'", a))?;
+            let b = parse_value(b, names, name_offset).ok_or_else(|| format!("Invalid value '{}'", b))?;
+            (a, b)
+        } else {
+            let v = parse_value(range, names, name_offset).ok_or_else(|| format!("Invalid value '{}'", range))?;
+            // "5/15" means "from 5 to the end, every 15"
+            (v, if part.contains('/') { max } else { v })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("Value out of range in '{}' (allowed {}-{})", part, min, max));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            mask[v as usize] = true;
+        }
+    }
+    Ok(mask)
+}
+
+impl CronSchedule {
+    fn parse(expr: &str) -> Result<Self, String> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday)",
+                expr
+            ));
+        }
+        let err = |e: String| format!("Invalid cron expression '{}': {}", expr, e);
+
+        let minutes = parse_field(fields[0], 0, 59, &[], 0).map_err(err)?;
+        let hours = parse_field(fields[1], 0, 23, &[], 0).map_err(err)?;
+        let days = parse_field(fields[2], 1, 31, &[], 0).map_err(err)?;
+        let months = parse_field(fields[3], 1, 12, &MONTH_NAMES, 1).map_err(err)?;
+        // Both 0 and 7 mean Sunday
+        let mut weekdays = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES, 0).map_err(err)?;
+        weekdays[0] |= weekdays[7];
+
+        let mut schedule = CronSchedule {
+            minutes: [false; 60],
+            hours: [false; 24],
+            days: [false; 32],
+            months: [false; 13],
+            weekdays: [false; 7],
+            // As in Vixie cron, "*/2" still counts as unrestricted
+            days_restricted: !fields[2].starts_with('*'),
+            weekdays_restricted: !fields[4].starts_with('*'),
+        };
+        schedule.minutes.copy_from_slice(&minutes);
+        schedule.hours.copy_from_slice(&hours);
+        schedule.days.copy_from_slice(&days);
+        schedule.months.copy_from_slice(&months);
+        schedule.weekdays.copy_from_slice(&weekdays[..7]);
+        Ok(schedule)
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days[date.day() as usize];
+        let weekday = self.weekdays[date.weekday().num_days_from_sunday() as usize];
+        // A plain "*" mask is all true, so this only narrows for "*/n"
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// First matching local time strictly after `after`, searching up to about four years ahead
+    fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut t: NaiveDateTime = after.naive_local().with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        let limit = t + ChronoDuration::days(366 * 4);
+
+        while t < limit {
+            if !self.months[t.month() as usize] || !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours[t.hour() as usize] {
+                t = t.with_minute(0)? + ChronoDuration::hours(1);
+                continue;
+            }
+            if !self.minutes[t.minute() as usize] {
+                t += ChronoDuration::minutes(1);
+                continue;
+            }
+            // Local times skipped by a DST jump don't exist; keep searching
+            if let Some(local) = Local.from_local_datetime(&t).earliest() {
+                return Some(local);
+            }
+            t += ChronoDuration::minutes(1);
+        }
+        None
+    }
+}
+
+fn compute_next_run(cron: &str, after: u64) -> Result<Option<u64>, String> {
+    let schedule = CronSchedule::parse(cron)?;
+    let after = Local
+        .timestamp_opt(after as i64, 0)
+        .single()
+        .ok_or_else(|| "Invalid timestamp".to_string())?;
+    Ok(schedule.next_after(after).map(|t| t.timestamp() as u64))
+}
+
+fn get_schedules_file_path() -> PathBuf {
+    get_metadata_file_path().with_file_name("schedules.json")
+}
+
+fn load_schedules() -> Vec<ScheduleRule> {
+    let path = get_schedules_file_path();
+    if path.exists() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(rules) = serde_json::from_str(&content) {
+                return rules;
+            }
+        }
+    }
+    vec![]
+}
+
+fn save_schedules(rules: &[ScheduleRule]) -> Result<(), String> {
+    let path = get_schedules_file_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        tick(&app);
+        std::thread::sleep(TICK_INTERVAL);
+    });
+}
+
+fn tick(app: &AppHandle) {
+    let now = unix_now();
+    let due: Vec<ScheduleRule> = {
+        let Ok(_guard) = SCHEDULES_LOCK.lock() else { return };
+        let mut rules = load_schedules();
+        let mut due = vec![];
+        let mut changed = false;
+        for rule in rules.iter_mut().filter(|r| r.enabled) {
+            match rule.next_run {
+                // Runs missed by more than a tick (app closed, machine asleep) are skipped, not replayed
+                Some(next) if next <= now && now - next <= TICK_INTERVAL.as_secs() * 2 => due.push(rule.clone()),
+                Some(next) if next <= now => {}
+                Some(_) => continue,
+                None => {}
+            }
+            rule.next_run = compute_next_run(&rule.cron, now).unwrap_or(None);
+            changed = true;
+        }
+        if changed {
+            if let Err(e) = save_schedules(&rules) {
+                eprintln!("Failed to save schedules: {}", e);
+            }
+        }
+        due
+    };
+
+    for rule in due {
+        let app = app.clone();
+        std::thread::spawn(move || run_rule(&app, rule));
+    }
+}
+
+fn run_rule(app: &AppHandle, rule: ScheduleRule) {
+    let result = bulk::run_action(std::slice::from_ref(&rule.label), rule.action.into(), 1)
+        .pop()
+        .ok_or_else(|| "No result".to_string())
+        .and_then(|r| if r.success { Ok(r.message) } else { Err(r.message) });
+    let success = result.is_ok();
+    let message = result.unwrap_or_else(|e| e);
+
+    if let Ok(_guard) = SCHEDULES_LOCK.lock() {
+        let mut rules = load_schedules();
+        if let Some(stored) = rules.iter_mut().find(|r| r.id == rule.id) {
+            stored.last_run = Some(unix_now());
+            stored.last_result = Some(message.clone());
+            if let Err(e) = save_schedules(&rules) {
+                eprintln!("Failed to save schedules: {}", e);
+            }
+        }
+    }
+
+    tray::refresh(app);
+    let _ = app.emit(
+        "schedule-executed",
+        ScheduleExecuted {
+            id: rule.id,
+            label: rule.label,
+            action: rule.action,
+            success,
+            message,
+        },
+    );
+}
+
+#[tauri::command]
+pub fn get_schedules() -> Result<Vec<ScheduleRule>, String> {
+    Ok(load_schedules())
+}
+
+/// Create a rule (when `id` is empty) or update an existing one
+#[tauri::command]
+pub fn save_schedule(mut rule: ScheduleRule) -> Result<ScheduleRule, String> {
+    // Validate even paused rules so they can be resumed later
+    CronSchedule::parse(&rule.cron)?;
+    let now = unix_now();
+    rule.next_run = if rule.enabled { compute_next_run(&rule.cron, now)? } else { None };
+
+    let _guard = SCHEDULES_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut rules = load_schedules();
+    if rule.id.is_empty() {
+        // Past the highest suffix in use, so ids stay unique after deletions
+        let next = rules
+            .iter()
+            .filter_map(|r| r.id.rsplit_once('-').and_then(|(_, n)| n.parse::<usize>().ok()))
+            .max()
+            .map_or(0, |n| n + 1);
+        rule.id = format!("{}-{}", now, next);
+        rules.push(rule.clone());
+    } else {
+        let existing = rules
+            .iter_mut()
+            .find(|r| r.id == rule.id)
+            .ok_or_else(|| format!("Schedule '{}' not found", rule.id))?;
+        rule.last_run = existing.last_run;
+        rule.last_result = existing.last_result.clone();
+        *existing = rule.clone();
+    }
+    save_schedules(&rules)?;
+    Ok(rule)
+}
+
+#[tauri::command]
+pub fn delete_schedule(id: String) -> Result<String, String> {
+    let _guard = SCHEDULES_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut rules = load_schedules();
+    let before = rules.len();
+    rules.retain(|r| r.id != id);
+    if rules.len() == before {
+        return Err(format!("Schedule '{}' not found", id));
+    }
+    save_schedules(&rules)?;
+    Ok("Schedule deleted successfully".to_string())
+}
+
+/// Pause or resume a rule
+#[tauri::command]
+pub fn set_schedule_enabled(id: String, enabled: bool) -> Result<ScheduleRule, String> {
+    let _guard = SCHEDULES_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut rules = load_schedules();
+    let rule = rules
+        .iter_mut()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Schedule '{}' not found", id))?;
+    rule.enabled = enabled;
+    rule.next_run = if enabled { compute_next_run(&rule.cron, unix_now())? } else { None };
+    let updated = rule.clone();
+    save_schedules(&rules)?;
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn values(mask: &[bool]) -> Vec<usize> {
+        mask.iter().enumerate().filter(|(_, on)| **on).map(|(i, _)| i).collect()
+    }
+
+    #[test]
+    fn parse_field_lists_ranges_and_steps() {
+        assert_eq!(values(&parse_field("1,5,10-12", 0, 59, &[], 0).unwrap()), vec![1, 5, 10, 11, 12]);
+        assert_eq!(values(&parse_field("*/15", 0, 59, &[], 0).unwrap()), vec![0, 15, 30, 45]);
+        assert_eq!(values(&parse_field("5/20", 0, 59, &[], 0).unwrap()), vec![5, 25, 45]);
+        assert_eq!(values(&parse_field("1-10/3", 0, 59, &[], 0).unwrap()), vec![1, 4, 7, 10]);
+        assert_eq!(values(&parse_field("jan,Mar-apr", 1, 12, &MONTH_NAMES, 1).unwrap()), vec![1, 3, 4]);
+        assert_eq!(values(&parse_field("mon-fri", 0, 7, &WEEKDAY_NAMES, 0).unwrap()), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn parse_field_rejects_bad_values() {
+        assert!(parse_field("60", 0, 59, &[], 0).is_err());
+        assert!(parse_field("0", 1, 31, &[], 0).is_err());
+        assert!(parse_field("10-5", 0, 59, &[], 0).is_err());
+        assert!(parse_field("*/0", 0, 59, &[], 0).is_err());
+        assert!(parse_field("abc", 0, 59, &[], 0).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_wrong_field_count() {
+        assert!(CronSchedule::parse("0 4 * *").is_err());
+        assert!(CronSchedule::parse("0 4 * * * *").is_err());
+        assert!(CronSchedule::parse("@yearly").is_err());
+    }
+
+    #[test]
+    fn seven_is_sunday() {
+        let schedule = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert!(schedule.weekdays[0]);
+    }
+
+    #[test]
+    fn day_fields_match_either_when_both_restricted() {
+        // 2024-05-01 is a Wednesday, 2024-05-06 a Monday
+        let schedule = CronSchedule::parse("0 0 1 * mon").unwrap();
+        assert!(schedule.matches_day(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()));
+        assert!(schedule.matches_day(NaiveDate::from_ymd_opt(2024, 5, 6).unwrap()));
+        assert!(!schedule.matches_day(NaiveDate::from_ymd_opt(2024, 5, 7).unwrap()));
+    }
+
+    #[test]
+    fn star_step_day_field_is_unrestricted() {
+        // Odd days AND Mondays, not odd days OR Mondays
+        let schedule = CronSchedule::parse("0 0 */2 * 1").unwrap();
+        assert!(schedule.matches_day(NaiveDate::from_ymd_opt(2024, 5, 13).unwrap()));
+        assert!(!schedule.matches_day(NaiveDate::from_ymd_opt(2024, 5, 6).unwrap()));
+        assert!(!schedule.matches_day(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()));
+    }
+
+    #[test]
+    fn next_after_finds_the_following_match() {
+        let daily = CronSchedule::parse("@daily").unwrap();
+        assert_eq!(daily.next_after(local(2024, 5, 1, 12, 0)), Some(local(2024, 5, 2, 0, 0)));
+
+        let weekdays = CronSchedule::parse("0 19 * * 1-5").unwrap();
+        // Friday evening after the run: next is Monday
+        assert_eq!(weekdays.next_after(local(2024, 5, 3, 19, 0)), Some(local(2024, 5, 6, 19, 0)));
+
+        let quarter = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(quarter.next_after(local(2024, 5, 1, 12, 7)), Some(local(2024, 5, 1, 12, 15)));
+
+        let leap = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(leap.next_after(local(2024, 3, 1, 0, 0)), Some(local(2028, 2, 29, 0, 0)));
+
+        let never = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert_eq!(never.next_after(local(2024, 1, 1, 0, 0)), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Start a service and wait in the backend until it is actually ready.
+//!
+//! Readiness is judged by the service's port and health URL. Every start is
+//! recorded with its time-to-healthy in `start_history.json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::logs::get_service_logs;
+use crate::{
+    dependencies, find_service, get_loaded_services, get_metadata_file_path, is_service_healthy, launchctl_load,
+    unix_now, LaunchAgent,
+};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+/// Number of stderr lines returned when a service exits before becoming ready
+const STDERR_TAIL_LINES: usize = 20;
+/// Start records kept per service
+const HISTORY_LIMIT: usize = 50;
+
+/// Serializes read-modify-write cycles on `start_history.json` between concurrent starts
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StartOutcome {
+    Ready {
+        elapsed_ms: u64,
+    },
+    TimedOut {
+        elapsed_ms: u64,
+    },
+    ExitedEarly {
+        elapsed_ms: u64,
+        exit_code: Option<i32>,
+        stderr_tail: Vec<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StartRecord {
+    /// Unix timestamp (seconds) when the start was issued
+    pub started_at: u64,
+    /// "ready", "timed_out" or "exited_early"
+    pub outcome: String,
+    /// Time until the service became healthy, or until we gave up
+    pub elapsed_ms: u64,
+}
+
+fn get_history_file_path() -> PathBuf {
+    get_metadata_file_path().with_file_name("start_history.json")
+}
+
+fn load_history() -> HashMap<String, Vec<StartRecord>> {
+    let path = get_history_file_path();
+    if path.exists() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(history) = serde_json::from_str(&content) {
+                return history;
+            }
+        }
+    }
+    HashMap::new()
+}
+
+fn record_start(label: &str, started_at: u64, outcome: &StartOutcome) -> Result<(), String> {
+    let (name, elapsed_ms) = match outcome {
+        StartOutcome::Ready { elapsed_ms } => ("ready", *elapsed_ms),
+        StartOutcome::TimedOut { elapsed_ms } => ("timed_out", *elapsed_ms),
+        StartOutcome::ExitedEarly { elapsed_ms, .. } => ("exited_early", *elapsed_ms),
+    };
+
+    let _guard = HISTORY_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut history = load_history();
+    let records = history.entry(label.to_string()).or_default();
+    records.push(StartRecord {
+        started_at,
+        outcome: name.to_string(),
+        elapsed_ms,
+    });
+    if records.len() > HISTORY_LIMIT {
+        let excess = records.len() - HISTORY_LIMIT;
+        records.drain(..excess);
+    }
+
+    let path = get_history_file_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&history).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Last lines of the service's stderr log, falling back to stdout when stderr is not redirected
+pub(crate) fn stderr_tail(service: &LaunchAgent) -> Vec<String> {
+    service
+        .standard_error_path
+        .as_ref()
+        .or(service.standard_out_path.as_ref())
+        .and_then(|path| get_service_logs(path.clone(), STDERR_TAIL_LINES).ok())
+        .unwrap_or_default()
+}
+
+/// Poll until the service is healthy, its job exits, or the timeout elapses
+pub(crate) fn wait_for_ready(service: &LaunchAgent, timeout: Duration) -> StartOutcome {
+    let start = Instant::now();
+    // Read at each return, so the time the checks themselves took is included
+    let elapsed_ms = || start.elapsed().as_millis() as u64;
+    loop {
+        if is_service_healthy(service) {
+            return StartOutcome::Ready { elapsed_ms: elapsed_ms() };
+        }
+
+        // A job that disappeared, or has no process and a failing exit status, won't become ready
+        match get_loaded_services().get(&service.label) {
+            None => {
+                return StartOutcome::ExitedEarly {
+                    elapsed_ms: elapsed_ms(),
+                    exit_code: None,
+                    stderr_tail: stderr_tail(service),
+                }
+            }
+            Some(state) if state.pid.is_none() && state.last_exit_status.is_some_and(|s| s != 0) => {
+                return StartOutcome::ExitedEarly {
+                    elapsed_ms: elapsed_ms(),
+                    exit_code: state.last_exit_status,
+                    stderr_tail: stderr_tail(service),
+                }
+            }
+            _ => {}
+        }
+
+        if start.elapsed() >= timeout {
+            return StartOutcome::TimedOut { elapsed_ms: elapsed_ms() };
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Start a service (dependencies first) and block until it is ready, times out, or exits
+#[tauri::command(async)]
+pub fn start_service_and_wait(label: String, timeout_secs: Option<u64>) -> Result<StartOutcome, String> {
+    let service = find_service(&label)?;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+    if service.is_loaded && is_service_healthy(&service) {
+        return Ok(StartOutcome::Ready { elapsed_ms: 0 });
+    }
+
+    dependencies::start_dependencies(&label)?;
+
+    let started_at = unix_now();
+    if !service.is_loaded {
+        launchctl_load(&service.file_path)?;
+    }
+
+    let outcome = wait_for_ready(&service, timeout);
+    if let Err(e) = record_start(&label, started_at, &outcome) {
+        eprintln!("Failed to record start history for {}: {}", label, e);
+    }
+    Ok(outcome)
+}
+
+#[tauri::command]
+pub fn get_start_history(label: String) -> Result<Vec<StartRecord>, String> {
+    Ok(load_history().remove(&label).unwrap_or_default())
+}
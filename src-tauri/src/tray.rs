@@ -0,0 +1,148 @@
+//! Menu bar tray: window controls plus one-click group and member toggles.
+
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::bulk::ServiceAction;
+use crate::groups;
+use crate::{collect_services, find_service, load_service, unload_service};
+
+const TRAY_ID: &str = "main";
+
+pub(crate) fn create(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id.as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Rebuild the tray menu so group membership and running state are current
+pub(crate) fn refresh(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        match build_menu(app) {
+            Ok(menu) => {
+                let _ = tray.set_menu(Some(menu));
+            }
+            Err(e) => eprintln!("Failed to rebuild tray menu: {}", e),
+        }
+    }
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+
+    let groups = groups::load_groups();
+    if !groups.is_empty() {
+        let services = collect_services().unwrap_or_default();
+        for group in &groups {
+            let members: Vec<_> = group
+                .labels
+                .iter()
+                .map(|label| (label, services.iter().find(|s| &s.label == label)))
+                .collect();
+            let running = members
+                .iter()
+                .filter(|(_, s)| s.is_some_and(|s| s.is_loaded))
+                .count();
+
+            let submenu = Submenu::new(
+                app,
+                format!("{} ({}/{})", group.name, running, members.len()),
+                true,
+            )?;
+            for (action, text) in [("start", "Start All"), ("stop", "Stop All"), ("restart", "Restart All")] {
+                submenu.append(&MenuItem::with_id(
+                    app,
+                    format!("group:{}:{}", action, group.name),
+                    text,
+                    true,
+                    None::<&str>,
+                )?)?;
+            }
+            submenu.append(&PredefinedMenuItem::separator(app)?)?;
+            for (label, service) in members {
+                let text = service
+                    .and_then(|s| s.display_name.clone())
+                    .unwrap_or_else(|| label.clone());
+                submenu.append(&CheckMenuItem::with_id(
+                    app,
+                    format!("service:toggle:{}", label),
+                    text,
+                    service.is_some(),
+                    service.is_some_and(|s| s.is_loaded),
+                    None::<&str>,
+                )?)?;
+            }
+            menu.append(&submenu)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    menu.append(&MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "quit" => app.exit(0),
+        "show" => show_main_window(app),
+        _ => {
+            let parts: Vec<&str> = id.splitn(3, ':').collect();
+            let (kind, action, target) = match parts.as_slice() {
+                [kind, action, target] => (kind.to_string(), action.to_string(), target.to_string()),
+                _ => return,
+            };
+            let app = app.clone();
+            let id = id.to_string();
+            // Service control blocks on launchctl and health checks, keep it off the main thread
+            std::thread::spawn(move || {
+                let result = match (kind.as_str(), action.as_str()) {
+                    ("group", "start") => groups::run_group_action(&target, ServiceAction::Start).map(|_| ()),
+                    ("group", "stop") => groups::run_group_action(&target, ServiceAction::Stop).map(|_| ()),
+                    ("group", "restart") => groups::run_group_action(&target, ServiceAction::Restart).map(|_| ()),
+                    ("service", "toggle") => toggle_service(&target),
+                    _ => Ok(()),
+                };
+                if let Err(e) = result {
+                    eprintln!("Tray action '{}' failed: {}", id, e);
+                }
+                refresh(&app);
+            });
+        }
+    }
+}
+
+fn toggle_service(label: &str) -> Result<(), String> {
+    let service = find_service(label)?;
+    if service.is_loaded {
+        unload_service(service.file_path)?;
+    } else {
+        load_service(service.file_path)?;
+    }
+    Ok(())
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
@@ -0,0 +1,380 @@
+//! Persistent uptime history.
+//!
+//! The health monitor hands every observation to `UptimeRecorder`, which appends
+//! it to `uptime/<label>.jsonl` next to `metadata.json`. To keep the files small
+//! only changes are written, plus a heartbeat every few minutes that marks the
+//! stretch as observed. Time spent stopped is excluded from availability; a
+//! loaded service that is crash looping or failing its checks counts as down.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::health::HealthStatus;
+use crate::{get_metadata_file_path, unix_now, ServiceState};
+
+/// Write an unchanged sample at least this often
+const HEARTBEAT_SECS: u64 = 300;
+/// Longer silences mean ServiceMaster wasn't running; that time isn't counted
+const MAX_SAMPLE_GAP_SECS: u64 = HEARTBEAT_SECS * 2;
+const RETENTION_SECS: u64 = 30 * 24 * 3600;
+const PRUNE_INTERVAL_SECS: u64 = 24 * 3600;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct UptimeSample {
+    pub at: u64,
+    pub state: ServiceState,
+    pub status: Option<HealthStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Availability {
+    Up,
+    Down,
+    Stopped,
+}
+
+impl UptimeSample {
+    fn availability(&self) -> Availability {
+        match (self.state, self.status) {
+            (ServiceState::Stopped, _) => Availability::Stopped,
+            (ServiceState::CrashLooping, _) | (_, Some(HealthStatus::Unhealthy)) => Availability::Down,
+            _ => Availability::Up,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Outage {
+    pub start: u64,
+    /// None while the outage is still ongoing
+    pub end: Option<u64>,
+    pub duration_secs: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UptimeStats {
+    pub label: String,
+    pub from: u64,
+    pub to: u64,
+    /// Up time over observed loaded time; None when nothing was observed
+    pub availability_percent: Option<f64>,
+    pub up_secs: u64,
+    pub down_secs: u64,
+    pub stopped_secs: u64,
+    pub outage_count: usize,
+    /// Mean duration of outages that have ended
+    pub mttr_secs: Option<u64>,
+    pub longest_outage_secs: u64,
+    pub outages: Vec<Outage>,
+}
+
+struct LastWrite {
+    sample: UptimeSample,
+    pruned_at: u64,
+}
+
+/// Managed state remembering the last sample written per service
+#[derive(Default)]
+pub struct UptimeRecorder {
+    last: Mutex<HashMap<String, LastWrite>>,
+}
+
+fn get_uptime_dir() -> PathBuf {
+    get_metadata_file_path().with_file_name("uptime")
+}
+
+fn get_uptime_file_path(label: &str) -> PathBuf {
+    let name: String = label
+        .chars()
+        .map(|c| if c.is_alphanumeric() || "._-".contains(c) { c } else { '_' })
+        .collect();
+    get_uptime_dir().join(format!("{}.jsonl", name))
+}
+
+fn load_samples(label: &str) -> Vec<UptimeSample> {
+    fs::read_to_string(get_uptime_file_path(label))
+        .map(|content| content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+fn append_sample(label: &str, sample: &UptimeSample) -> Result<(), String> {
+    let path = get_uptime_file_path(label);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let line = serde_json::to_string(sample).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+/// Drop samples older than the retention window
+fn prune(label: &str, now: u64) -> Result<(), String> {
+    let path = get_uptime_file_path(label);
+    if !path.exists() {
+        return Ok(());
+    }
+    let samples = load_samples(label);
+    let cutoff = now.saturating_sub(RETENTION_SECS);
+    if samples.first().is_none_or(|s| s.at >= cutoff) {
+        return Ok(());
+    }
+    let mut content = String::new();
+    for sample in samples.iter().filter(|s| s.at >= cutoff) {
+        content.push_str(&serde_json::to_string(sample).map_err(|e| e.to_string())?);
+        content.push('\n');
+    }
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+impl UptimeRecorder {
+    /// Record an observation; unchanged observations are only written as heartbeats
+    pub(crate) fn record(&self, label: &str, state: ServiceState, status: Option<HealthStatus>) {
+        let now = unix_now();
+        let sample = UptimeSample { at: now, state, status };
+        let Ok(mut last) = self.last.lock() else { return };
+
+        let previous = last.get(label);
+        let changed = previous.is_none_or(|p| p.sample.state != state || p.sample.status != status);
+        let heartbeat_due = previous.is_none_or(|p| now.saturating_sub(p.sample.at) >= HEARTBEAT_SECS);
+        if !changed && !heartbeat_due {
+            return;
+        }
+
+        if previous.is_none_or(|p| now.saturating_sub(p.pruned_at) >= PRUNE_INTERVAL_SECS) {
+            if let Err(e) = prune(label, now) {
+                eprintln!("Failed to prune uptime history for {}: {}", label, e);
+            }
+        }
+        let pruned_at = match previous {
+            Some(p) if now.saturating_sub(p.pruned_at) < PRUNE_INTERVAL_SECS => p.pruned_at,
+            _ => now,
+        };
+
+        if let Err(e) = append_sample(label, &sample) {
+            eprintln!("Failed to record uptime for {}: {}", label, e);
+        }
+        last.insert(label.to_string(), LastWrite { sample, pruned_at });
+    }
+}
+
+/// Parse a range such as "90m", "24h", "7d" or "4w" into seconds
+fn parse_range(range: &str) -> Result<u64, String> {
+    let range = range.trim();
+    let split = range.find(|c: char| !c.is_ascii_digit()).unwrap_or(range.len());
+    let (amount, unit) = range.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| format!("Invalid range '{}'", range))?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 3600,
+        "" | "d" => 86400,
+        "w" => 7 * 86400,
+        _ => return Err(format!("Invalid range unit in '{}', use m, h, d or w", range)),
+    };
+    amount
+        .checked_mul(unit_secs)
+        .ok_or_else(|| format!("Range '{}' is too large", range))
+}
+
+fn compute_stats(label: &str, samples: &[UptimeSample], from: u64, to: u64) -> UptimeStats {
+    let mut stats = UptimeStats {
+        label: label.to_string(),
+        from,
+        to,
+        availability_percent: None,
+        up_secs: 0,
+        down_secs: 0,
+        stopped_secs: 0,
+        outage_count: 0,
+        mttr_secs: None,
+        longest_outage_secs: 0,
+        outages: vec![],
+    };
+    // Start of the outage in progress
+    let mut current: Option<u64> = None;
+
+    for (i, sample) in samples.iter().enumerate() {
+        // A sample stands for the time until the next one, bounded by the heartbeat gap
+        let next_at = samples.get(i + 1).map_or(to, |next| next.at);
+        let start = sample.at.max(from);
+        let end = next_at.min(sample.at + MAX_SAMPLE_GAP_SECS).min(to);
+        if end <= start && sample.at < from {
+            continue;
+        }
+        let covered = end.saturating_sub(start);
+
+        match sample.availability() {
+            Availability::Up => stats.up_secs += covered,
+            Availability::Down => stats.down_secs += covered,
+            Availability::Stopped => stats.stopped_secs += covered,
+        }
+
+        match (sample.availability(), current) {
+            (Availability::Down, _) => {
+                let outage_start = *current.get_or_insert(start);
+                // Like down_secs, an outage stops where observation does
+                if end < next_at.min(to) {
+                    stats.outages.push(Outage {
+                        start: outage_start,
+                        end: Some(end),
+                        duration_secs: end - outage_start,
+                    });
+                    current = None;
+                }
+            }
+            (Availability::Up | Availability::Stopped, Some(outage_start)) => {
+                stats.outages.push(Outage {
+                    start: outage_start,
+                    end: Some(start),
+                    duration_secs: start - outage_start,
+                });
+                current = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(outage_start) = current {
+        stats.outages.push(Outage {
+            start: outage_start,
+            end: None,
+            duration_secs: to.saturating_sub(outage_start),
+        });
+    }
+
+    let observed = stats.up_secs + stats.down_secs;
+    if observed > 0 {
+        stats.availability_percent = Some(stats.up_secs as f64 * 100.0 / observed as f64);
+    }
+    stats.outage_count = stats.outages.len();
+    stats.longest_outage_secs = stats.outages.iter().map(|o| o.duration_secs).max().unwrap_or(0);
+    let recovered: Vec<u64> = stats
+        .outages
+        .iter()
+        .filter(|o| o.end.is_some())
+        .map(|o| o.duration_secs)
+        .collect();
+    if !recovered.is_empty() {
+        stats.mttr_secs = Some(recovered.iter().sum::<u64>() / recovered.len() as u64);
+    }
+    stats
+}
+
+/// Availability, outages and recovery times for a service over `range` (e.g. "24h", "7d")
+#[tauri::command]
+pub fn get_service_uptime(label: String, range: String) -> Result<UptimeStats, String> {
+    let to = unix_now();
+    let from = to.saturating_sub(parse_range(&range)?);
+
+    let samples = load_samples(&label);
+    // Keep the last sample before the range so the state at `from` is known
+    let first = samples.iter().rposition(|s| s.at <= from).unwrap_or(0);
+    let samples: Vec<UptimeSample> = samples[first..].iter().filter(|s| s.at <= to).copied().collect();
+
+    Ok(compute_stats(&label, &samples, from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(at: u64, state: ServiceState, status: Option<HealthStatus>) -> UptimeSample {
+        UptimeSample { at, state, status }
+    }
+
+    fn up(at: u64) -> UptimeSample {
+        sample(at, ServiceState::Running, Some(HealthStatus::Healthy))
+    }
+
+    fn down(at: u64) -> UptimeSample {
+        sample(at, ServiceState::Running, Some(HealthStatus::Unhealthy))
+    }
+
+    fn stopped(at: u64) -> UptimeSample {
+        sample(at, ServiceState::Stopped, None)
+    }
+
+    #[test]
+    fn empty_history_has_no_availability() {
+        let stats = compute_stats("a", &[], 0, 1000);
+        assert_eq!(stats.availability_percent, None);
+        assert_eq!(stats.up_secs + stats.down_secs + stats.stopped_secs, 0);
+        assert_eq!(stats.outage_count, 0);
+        assert_eq!(stats.mttr_secs, None);
+    }
+
+    #[test]
+    fn unobserved_gaps_are_not_counted() {
+        // The app was closed after the first sample
+        let stats = compute_stats("a", &[up(0), up(10_000)], 0, 10_100);
+        assert_eq!(stats.up_secs, MAX_SAMPLE_GAP_SECS + 100);
+        assert_eq!(stats.availability_percent, Some(100.0));
+    }
+
+    #[test]
+    fn outages_stop_at_gaps_like_down_time() {
+        let stats = compute_stats("a", &[up(0), down(100), up(5_000)], 0, 5_100);
+        assert_eq!(stats.down_secs, MAX_SAMPLE_GAP_SECS);
+        assert_eq!(stats.outage_count, 1);
+        assert_eq!(stats.outages[0].start, 100);
+        assert_eq!(stats.outages[0].end, Some(100 + MAX_SAMPLE_GAP_SECS));
+        assert_eq!(stats.longest_outage_secs, stats.down_secs);
+    }
+
+    #[test]
+    fn outage_spans_heartbeats_and_recovers() {
+        let stats = compute_stats("a", &[down(0), down(300), down(600), up(900)], 0, 1_000);
+        assert_eq!(stats.outage_count, 1);
+        assert_eq!(stats.outages[0].end, Some(900));
+        assert_eq!(stats.mttr_secs, Some(900));
+        assert_eq!(stats.down_secs, 900);
+        assert_eq!(stats.up_secs, 100);
+    }
+
+    #[test]
+    fn outages_are_clipped_to_the_range() {
+        // Down since before the range started
+        let stats = compute_stats("a", &[down(900), up(1_200)], 1_000, 1_500);
+        assert_eq!(stats.outages[0].start, 1_000);
+        assert_eq!(stats.outages[0].duration_secs, 200);
+        assert_eq!(stats.down_secs, 200);
+
+        // Still down when the range ends
+        let stats = compute_stats("a", &[up(0), down(300)], 0, 500);
+        assert_eq!(stats.outages[0].end, None);
+        assert_eq!(stats.outages[0].duration_secs, 200);
+        assert_eq!(stats.mttr_secs, None);
+    }
+
+    #[test]
+    fn stopping_ends_an_outage_and_is_excluded() {
+        let stats = compute_stats("a", &[down(0), stopped(100), up(400)], 0, 500);
+        assert_eq!(stats.outages[0].end, Some(100));
+        assert_eq!(stats.stopped_secs, 300);
+        assert_eq!(stats.availability_percent, Some(50.0));
+    }
+
+    #[test]
+    fn parse_range_units() {
+        assert_eq!(parse_range("90m"), Ok(5_400));
+        assert_eq!(parse_range("24h"), Ok(86_400));
+        assert_eq!(parse_range(" 7d "), Ok(7 * 86_400));
+        assert_eq!(parse_range("2"), Ok(2 * 86_400));
+        assert_eq!(parse_range("4w"), Ok(28 * 86_400));
+        assert!(parse_range("5y").is_err());
+        assert!(parse_range("h").is_err());
+        assert!(parse_range("").is_err());
+    }
+
+    #[test]
+    fn parse_range_rejects_overflow() {
+        assert!(parse_range("307445734561825861m").unwrap_err().contains("too large"));
+        assert!(parse_range("99999999999999999999d").is_err());
+    }
+}
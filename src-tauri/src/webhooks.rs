@@ -0,0 +1,405 @@
+//! Outbound webhooks for service events.
+//!
+//! Targets live in `AppSettings`. Each service event from the monitor is rendered
+//! into the target's format (plain JSON, a Slack message or an ntfy push) and
+//! delivered on a background thread with exponential backoff. Every delivery,
+//! successful or not, is appended to `webhook_deliveries.json`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::health::HealthStatus;
+use crate::monitor::ServiceEvent;
+use crate::{find_service, get_app_settings, get_metadata_file_path, startup, unix_now, ServiceState};
+
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries kept in the log
+const DELIVERY_LOG_LIMIT: usize = 200;
+
+static DELIVERY_LOG_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// POST the event as JSON, or the rendered `template`
+    #[default]
+    Generic,
+    /// Slack-compatible incoming webhook (`{"text": ...}`)
+    Slack,
+    /// ntfy topic URL; the message is the body, the title a header
+    Ntfy,
+}
+
+/// A webhook destination stored in `AppSettings`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookTarget {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    pub url: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Event names to send (e.g. "crashed", "unhealthy"); empty sends all
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Only send events for these labels; empty sends all
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Message template with `{{label}}`, `{{display_name}}`, `{{event}}`, `{{message}}`,
+    /// `{{exit_code}}` and `{{log_tail}}`. For generic targets the values are JSON-escaped,
+    /// so the template can be a JSON document.
+    pub template: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Values available to templates; also the default generic payload
+#[derive(Debug, Serialize, Clone)]
+pub struct WebhookPayload {
+    pub label: String,
+    pub display_name: String,
+    pub event: String,
+    pub message: String,
+    pub exit_code: Option<i32>,
+    pub log_tail: Vec<String>,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeliveryRecord {
+    pub target_id: String,
+    pub target_name: String,
+    pub label: String,
+    pub event: String,
+    /// Unix timestamp (seconds) of the first attempt
+    pub sent_at: u64,
+    pub attempts: u32,
+    pub success: bool,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+fn get_delivery_log_path() -> PathBuf {
+    get_metadata_file_path().with_file_name("webhook_deliveries.json")
+}
+
+fn load_deliveries() -> Vec<DeliveryRecord> {
+    let path = get_delivery_log_path();
+    if path.exists() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(records) = serde_json::from_str(&content) {
+                return records;
+            }
+        }
+    }
+    vec![]
+}
+
+fn log_delivery(record: DeliveryRecord) -> Result<(), String> {
+    let _guard = DELIVERY_LOG_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut records = load_deliveries();
+    records.push(record);
+    if records.len() > DELIVERY_LOG_LIMIT {
+        let excess = records.len() - DELIVERY_LOG_LIMIT;
+        records.drain(..excess);
+    }
+
+    let path = get_delivery_log_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Event name, message, exit code and whether the event is a failure
+fn describe(event: &ServiceEvent) -> Option<(&str, String, String, Option<i32>, bool)> {
+    Some(match event {
+        ServiceEvent::StateChanged { label, to, .. } => {
+            let (name, message, failure) = match to {
+                ServiceState::Running => ("started", "is running", false),
+                ServiceState::Stopped => ("stopped", "was stopped", false),
+                ServiceState::CrashLooping => ("crash_looping", "is crash looping", true),
+            };
+            (label.as_str(), name.to_string(), message.to_string(), None, failure)
+        }
+        // A service's first check after launch or a start isn't a recovery
+        ServiceEvent::HealthChanged {
+            from: None,
+            to: HealthStatus::Healthy,
+            ..
+        } => return None,
+        ServiceEvent::HealthChanged { label, to, result, .. } => {
+            let (name, failure) = match to {
+                HealthStatus::Healthy => ("healthy", false),
+                HealthStatus::Degraded => ("degraded", false),
+                HealthStatus::Unhealthy => ("unhealthy", true),
+            };
+            (label.as_str(), name.to_string(), result.message.clone(), None, failure)
+        }
+        ServiceEvent::Crashed { label, exit_status } => (
+            label.as_str(),
+            "crashed".to_string(),
+            "crashed and was restarted".to_string(),
+            *exit_status,
+            true,
+        ),
+        ServiceEvent::Exited { label, exit_code } => (
+            label.as_str(),
+            "exited".to_string(),
+            format!("exited with status {}", exit_code),
+            Some(*exit_code),
+            true,
+        ),
+        // Individual failed checks would flood a channel; health transitions cover them
+        ServiceEvent::CheckFailed { .. } => return None,
+    })
+}
+
+fn build_payload(event: &ServiceEvent) -> Option<WebhookPayload> {
+    let (label, name, message, exit_code, failure) = describe(event)?;
+    let service = find_service(label).ok();
+    let display_name = service
+        .as_ref()
+        .and_then(|s| s.display_name.clone())
+        .unwrap_or_else(|| label.to_string());
+    // Log lines only help when something went wrong
+    let log_tail = match (&service, failure) {
+        (Some(service), true) => startup::stderr_tail(service),
+        _ => vec![],
+    };
+    Some(WebhookPayload {
+        label: label.to_string(),
+        display_name,
+        event: name,
+        message,
+        exit_code,
+        log_tail,
+        timestamp: unix_now(),
+    })
+}
+
+fn render(template: &str, payload: &WebhookPayload, escape_json: bool) -> String {
+    let escape = |value: &str| {
+        if escape_json {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    let exit_code = payload.exit_code.map(|c| c.to_string()).unwrap_or_default();
+    template
+        .replace("{{label}}", &escape(&payload.label))
+        .replace("{{display_name}}", &escape(&payload.display_name))
+        .replace("{{event}}", &escape(&payload.event))
+        .replace("{{message}}", &escape(&payload.message))
+        .replace("{{exit_code}}", &escape(&exit_code))
+        .replace("{{log_tail}}", &escape(&payload.log_tail.join("\n")))
+}
+
+fn default_text(payload: &WebhookPayload) -> String {
+    let mut text = format!("{} {}: {}", payload.display_name, payload.event, payload.message);
+    if !payload.log_tail.is_empty() {
+        text.push_str(&format!("\n```\n{}\n```", payload.log_tail.join("\n")));
+    }
+    text
+}
+
+/// One HTTP attempt; returns the status code of a successful response
+fn send_once(
+    client: &reqwest::blocking::Client,
+    target: &WebhookTarget,
+    payload: &WebhookPayload,
+) -> Result<u16, (Option<u16>, String)> {
+    let mut request = client.post(&target.url);
+    for (name, value) in &target.headers {
+        request = request.header(name, value);
+    }
+    request = match target.kind {
+        WebhookKind::Generic => match &target.template {
+            Some(template) => request
+                .header("Content-Type", "application/json")
+                .body(render(template, payload, true)),
+            None => request.json(payload),
+        },
+        WebhookKind::Slack => {
+            let text = match &target.template {
+                Some(template) => render(template, payload, false),
+                None => default_text(payload),
+            };
+            request.json(&serde_json::json!({ "text": text }))
+        }
+        WebhookKind::Ntfy => {
+            let body = match &target.template {
+                Some(template) => render(template, payload, false),
+                None => format!("{}\n{}", payload.message, payload.log_tail.join("\n")).trim().to_string(),
+            };
+            request
+                .header("Title", format!("{} {}", payload.display_name, payload.event))
+                .header("Tags", payload.event.clone())
+                .body(body)
+        }
+    };
+
+    let response = request.send().map_err(|e| (None, e.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((Some(status.as_u16()), format!("HTTP {}", status)))
+    }
+}
+
+/// Deliver with retries, blocking until done, and record the outcome
+fn deliver(target: &WebhookTarget, payload: &WebhookPayload) -> DeliveryRecord {
+    let mut record = DeliveryRecord {
+        target_id: target.id.clone(),
+        target_name: target.name.clone(),
+        label: payload.label.clone(),
+        event: payload.event.clone(),
+        sent_at: unix_now(),
+        attempts: 0,
+        success: false,
+        http_status: None,
+        error: None,
+    };
+
+    let client = match reqwest::blocking::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            record.error = Some(e.to_string());
+            return record;
+        }
+    };
+
+    let mut backoff = INITIAL_BACKOFF;
+    while record.attempts < MAX_ATTEMPTS {
+        record.attempts += 1;
+        match send_once(&client, target, payload) {
+            Ok(status) => {
+                record.success = true;
+                record.http_status = Some(status);
+                record.error = None;
+                break;
+            }
+            Err((status, error)) => {
+                record.http_status = status;
+                record.error = Some(error);
+                // Client errors other than rate limiting won't succeed on retry
+                if status.is_some_and(|s| (400..500).contains(&s) && s != 429) {
+                    break;
+                }
+            }
+        }
+        if record.attempts < MAX_ATTEMPTS {
+            std::thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+
+    if let Err(e) = log_delivery(record.clone()) {
+        eprintln!("Failed to record webhook delivery: {}", e);
+    }
+    record
+}
+
+fn wants(target: &WebhookTarget, payload: &WebhookPayload) -> bool {
+    target.enabled
+        && (target.events.is_empty() || target.events.contains(&payload.event))
+        && (target.labels.is_empty() || target.labels.contains(&payload.label))
+}
+
+/// Send an event to every matching webhook target in the background
+pub(crate) fn dispatch(event: &ServiceEvent) {
+    let targets = get_app_settings().unwrap_or_default().webhooks;
+    if targets.iter().all(|t| !t.enabled) {
+        return;
+    }
+    let Some(payload) = build_payload(event) else { return };
+
+    for target in targets.into_iter().filter(|t| wants(t, &payload)) {
+        let payload = payload.clone();
+        std::thread::spawn(move || {
+            deliver(&target, &payload);
+        });
+    }
+}
+
+#[tauri::command]
+pub fn get_webhook_deliveries() -> Result<Vec<DeliveryRecord>, String> {
+    let mut records = load_deliveries();
+    records.reverse();
+    Ok(records)
+}
+
+/// Send a sample event to a target and wait for the result
+#[tauri::command(async)]
+pub fn test_webhook(target: WebhookTarget) -> Result<DeliveryRecord, String> {
+    let payload = WebhookPayload {
+        label: "com.example.test".to_string(),
+        display_name: "ServiceMaster".to_string(),
+        event: "test".to_string(),
+        message: "This is a test notification".to_string(),
+        exit_code: None,
+        log_tail: vec![],
+        timestamp: unix_now(),
+    };
+    Ok(deliver(&target, &payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::{CheckFailure, FailureCategory, HealthResult};
+
+    fn health_changed(from: Option<HealthStatus>, to: HealthStatus) -> ServiceEvent {
+        let result = match to {
+            HealthStatus::Unhealthy => {
+                HealthResult::unhealthy(5, None, CheckFailure::new(FailureCategory::Timeout, "timed out"))
+            }
+            _ => HealthResult::healthy(5, Some(200), "ok"),
+        };
+        ServiceEvent::HealthChanged {
+            label: "com.example.app".to_string(),
+            from,
+            to,
+            result,
+        }
+    }
+
+    #[test]
+    fn first_healthy_check_is_not_sent() {
+        assert!(describe(&health_changed(None, HealthStatus::Healthy)).is_none());
+    }
+
+    #[test]
+    fn first_unhealthy_check_is_sent() {
+        let event = health_changed(None, HealthStatus::Unhealthy);
+        let (_, name, _, _, failure) = describe(&event).unwrap();
+        assert_eq!(name, "unhealthy");
+        assert!(failure);
+    }
+
+    #[test]
+    fn recovery_is_sent() {
+        let event = health_changed(Some(HealthStatus::Unhealthy), HealthStatus::Healthy);
+        let (label, name, message, _, failure) = describe(&event).unwrap();
+        assert_eq!(label, "com.example.app");
+        assert_eq!(name, "healthy");
+        assert_eq!(message, "ok");
+        assert!(!failure);
+    }
+}
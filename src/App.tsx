@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { save, open as openDialog } from "@tauri-apps/plugin-dialog";
 import { writeTextFile, readTextFile } from "@tauri-apps/plugin-fs";
 import {
@@ -21,6 +22,57 @@ import {
 import { CSS } from "@dnd-kit/utilities";
 import { restrictToVerticalAxis } from "@dnd-kit/modifiers";
 
+interface HealthCheckSpec {
+  method: string;
+  headers: Record<string, string>;
+  body: string | null;
+  expected_status: number[];
+  body_contains: string | null;
+  body_regex: string | null;
+  json_assertions: { path: string; equals: unknown }[];
+  timeout_ms: number | null;
+  verify_tls: boolean;
+  degraded_latency_ms: number | null;
+}
+
+interface PortCheckSpec {
+  host: string | null;
+  family: "any" | "ipv4" | "ipv6";
+  protocol: "tcp" | "udp";
+  timeout_ms: number | null;
+}
+
+interface CommandCheckSpec {
+  command: string;
+  expected_exit_code: number;
+  stdout_contains: string | null;
+  timeout_ms: number | null;
+}
+
+type ProbeSpec = (
+  | { type: "web_socket"; path: string; tls: boolean }
+  | { type: "redis"; password: string | null }
+  | { type: "postgres" }
+  | { type: "mysql" }
+  | { type: "tls_cert"; warn_days: number | null }
+) & { host: string | null; port: number | null; timeout_ms: number | null };
+
+interface SocketCheckSpec {
+  path: string;
+  http_path: string | null;
+  http: HealthCheckSpec | null;
+}
+
+interface HealthResult {
+  status: "healthy" | "degraded" | "unhealthy";
+  http_status: number | null;
+  latency_ms: number;
+  failure: "dns" | "connection_refused" | "timeout" | "tls" | "bad_status" | "assertion_failed" | "invalid_config" | "other" | null;
+  message: string;
+  checked_at: number;
+  output: string | null;
+}
+
 interface LaunchAgent {
   label: string;
   program: string | null;
@@ -34,14 +86,22 @@ interface LaunchAgent {
   file_path: string;
   is_loaded: boolean;
   pid: number | null;
+  state: "stopped" | "running" | "crash_looping";
   display_name: string | null;
   description: string | null;
   icon: string | null;
   port: number | null;
+  port_check: PortCheckSpec | null;
   health_url: string | null;
+  health_check: HealthCheckSpec | null;
+  command_check: CommandCheckSpec | null;
+  probe: ProbeSpec | null;
+  socket_check: SocketCheckSpec | null;
+  on_demand: { backend_port: number; idle_timeout_secs: number; ready_timeout_secs: number } | null;
   project_path: string | null;
   app_path: string | null;
   order: number | null;
+  depends_on: string[];
 }
 
 interface ServiceMetadata {
@@ -53,6 +113,7 @@ interface ServiceMetadata {
   project_path: string | null;
   app_path: string | null;
   order: number | null;
+  depends_on?: string[];
 }
 
 interface AppSettings {
@@ -64,8 +125,185 @@ interface AppSettings {
   webdav_username: string | null;
   webdav_password: string | null;
   auto_launch: boolean | null;
+  notifications?: NotificationSettings;
+  webhooks?: WebhookTarget[];
+}
+
+interface WebhookTarget {
+  id: string;
+  name: string;
+  kind: "generic" | "slack" | "ntfy";
+  url: string;
+  enabled: boolean;
+  events: string[];
+  labels: string[];
+  template: string | null;
+  headers: Record<string, string>;
+}
+
+interface NotificationSettings {
+  muted: boolean;
+  muted_services: string[];
+  quiet_hours: { start: string; end: string } | null;
+  // 单个服务的免打扰时段，覆盖全局设置
+  service_quiet_hours: Record<string, { start: string; end: string }>;
+  on_crash: boolean;
+  on_exit: boolean;
+  on_health_failure: boolean;
+  on_recovery: boolean;
+  health_failure_threshold: number;
+  debounce_secs: number;
+}
+
+const DEFAULT_NOTIFICATIONS: NotificationSettings = {
+  muted: false,
+  muted_services: [],
+  quiet_hours: null,
+  service_quiet_hours: {},
+  on_crash: true,
+  on_exit: true,
+  on_health_failure: true,
+  on_recovery: true,
+  health_failure_threshold: 3,
+  debounce_secs: 300,
+};
+
+interface ServiceHealth {
+  label: string;
+  state: LaunchAgent["state"];
+  status: HealthResult["status"] | null;
+  checks: { port: HealthResult | null; http: HealthResult | null; command: HealthResult | null; probe: HealthResult | null; socket: HealthResult | null };
+  history: { checked_at: number; status: HealthResult["status"]; latency_ms: number }[];
+  interval_secs: number;
+  next_check_at: number;
+}
+
+interface LogLinesEvent {
+  seq: number;
+  label: string;
+  stream: "stdout" | "stderr";
+  lines: string[];
+  offsets: (number | null)[];
+  reset: boolean;
+  end_offset: number;
+}
+
+interface FollowPosition {
+  stream: "stdout" | "stderr";
+  path: string;
+  offset: number;
+}
+
+const MAX_LIVE_LOG_LINES = 2000;
+
+interface LogLine {
+  stream: "stdout" | "stderr";
+  line: string;
+  // 行在文件中的起始位置，用于向前翻页
+  offset?: number | null;
+  // 实时事件的序号，历史日志没有
+  seq?: number;
+}
+
+interface MergedLogPage {
+  lines: (LogLine & { timestamp: number | null })[];
+  interleaved: boolean;
+}
+
+interface LogMatch {
+  stream: "stdout" | "stderr";
+  file: string;
+  line_number: number;
+  line: string;
+  timestamp: number | null;
+  before: string[];
+  after: string[];
+}
+
+interface LogSearchResult {
+  matches: LogMatch[];
+  truncated: boolean;
+  files_searched: string[];
+}
+
+interface LogDiskUsage {
+  label: string;
+  total_bytes: number;
+  files: { stream: "stdout" | "stderr"; path: string; current_bytes: number; archived_bytes: number; archives: string[] }[];
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  if (bytes < 1024 * 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+  return `${(bytes / 1024 / 1024 / 1024).toFixed(2)} GB`;
+}
+
+type LogLevel = "trace" | "debug" | "info" | "warn" | "error" | "fatal";
+
+interface StyledSegment {
+  text: string;
+  fg: string | null;
+  bg: string | null;
+  bold: boolean;
+  dim: boolean;
+  italic: boolean;
+  underline: boolean;
+}
+
+interface LogRecord {
+  text: string;
+  message: string;
+  timestamp: number | null;
+  level: LogLevel | null;
+  fields: Record<string, unknown> | null;
+  segments: StyledSegment[];
+}
+
+interface StructuredLogPage {
+  records: LogRecord[];
+  start_offset: number;
+  has_more: boolean;
+}
+
+const LEVEL_COLORS: Record<LogLevel, string | undefined> = {
+  trace: "var(--text-secondary)",
+  debug: "var(--text-secondary)",
+  info: undefined,
+  warn: "#f59e0b",
+  error: "#ef4444",
+  fatal: "#dc2626",
+};
+
+const ANSI_COLORS: Record<string, string> = {
+  black: "#000000", red: "#cd3131", green: "#0dbc79", yellow: "#e5e510", blue: "#2472c8", magenta: "#bc3fbc", cyan: "#11a8cd", white: "#e5e5e5",
+  bright_black: "#666666", bright_red: "#f14c4c", bright_green: "#23d18b", bright_yellow: "#f5f543", bright_blue: "#3b8eea", bright_magenta: "#d670d6", bright_cyan: "#29b8db", bright_white: "#ffffff",
+};
+
+function segmentStyle(seg: StyledSegment): React.CSSProperties {
+  return {
+    color: seg.fg ? ANSI_COLORS[seg.fg] || seg.fg : undefined,
+    backgroundColor: seg.bg ? ANSI_COLORS[seg.bg] || seg.bg : undefined,
+    fontWeight: seg.bold ? "bold" : undefined,
+    opacity: seg.dim ? 0.6 : undefined,
+    fontStyle: seg.italic ? "italic" : undefined,
+    textDecoration: seg.underline ? "underline" : undefined,
+  };
+}
+
+interface LogPage {
+  lines: string[];
+  offsets: number[];
+  start_offset: number;
+  end_offset: number;
+  has_more: boolean;
 }
 
+type StartOutcome =
+  | { status: "ready"; elapsed_ms: number }
+  | { status: "timed_out"; elapsed_ms: number }
+  | { status: "exited_early"; elapsed_ms: number; exit_code: number | null; stderr_tail: string[] };
+
 interface UpdateInfo {
   available: boolean;
   currentVersion: string;
@@ -719,15 +957,72 @@ function ServiceModal({
 }
 
 function LogPanel({ isOpen, onClose, service }: { isOpen: boolean; onClose: () => void; service: LaunchAgent | null }) {
-  const [logs, setLogs] = useState<string[]>([]);
-  const [autoRefresh] = useState(true);
+  const [logs, setLogs] = useState<LogLine[]>([]);
+  // 合并显示 stdout 与 stderr，每行标注来源
+  const [merged, setMerged] = useState(false);
+  const [searchPattern, setSearchPattern] = useState("");
+  const [searchRegex, setSearchRegex] = useState(false);
+  const [searchResult, setSearchResult] = useState<LogSearchResult | null>(null);
+  const [diskUsage, setDiskUsage] = useState<LogDiskUsage | null>(null);
+  // 按级别过滤时改为显示后端解析后的结构化记录
+  const [minLevel, setMinLevel] = useState<LogLevel | "">("");
+  const [records, setRecords] = useState<LogRecord[] | null>(null);
+
+  const fetchRecords = async () => {
+    if (!service?.standard_out_path || !minLevel) { setRecords(null); return; }
+    try {
+      const page = await invoke<StructuredLogPage>("get_structured_logs", { logPath: service.standard_out_path, lines: 200, filter: { min_level: minLevel } });
+      setRecords(page.records);
+    } catch (e) { alert(`读取日志失败: ${e}`); }
+  };
 
-  const fetchLogs = async () => {
-    if (!service?.standard_out_path) return;
+  useEffect(() => { if (isOpen) fetchRecords(); }, [isOpen, service, minLevel]);
+
+  const fetchDiskUsage = async () => {
+    if (!service) return;
     try {
-      const result = await invoke<string[]>("get_service_logs", { logPath: service.standard_out_path, lines: 100 });
-      setLogs(result);
-    } catch (e) { setLogs([`Error: ${e}`]); }
+      const usage = await invoke<LogDiskUsage[]>("get_log_disk_usage", { label: service.label });
+      setDiskUsage(usage[0] || null);
+    } catch (e) { setDiskUsage(null); }
+  };
+
+  const handleRotate = async () => {
+    if (!service) return;
+    try { await invoke("rotate_service_logs", { label: service.label }); fetchDiskUsage(); } catch (e) { alert(`轮转失败: ${e}`); }
+  };
+
+  // 读取跟随起点之前的历史日志，之后的行由实时事件补上
+  const fetchLogs = async (positions: FollowPosition[]): Promise<LogLine[]> => {
+    if (!service) return [];
+    const before = (stream: FollowPosition["stream"]) => positions.find(p => p.stream === stream)?.offset;
+    if (merged) {
+      const page = await invoke<MergedLogPage>("read_merged_service_logs", { label: service.label, lines: 200, stdoutBefore: before("stdout"), stderrBefore: before("stderr") });
+      return page.lines.map(l => ({ stream: l.stream, line: l.line }));
+    }
+    if (!service.standard_out_path) return [];
+    const page = await invoke<LogPage>("read_service_logs", { logPath: service.standard_out_path, lines: 100, before: before("stdout") });
+    return page.lines.map((line, i) => ({ stream: "stdout", line, offset: page.offsets[i] }));
+  };
+
+  // 向前翻页从当前显示的最早一行开始，实时日志超出上限被裁掉时随之后移
+  const oldestShown = logs.find(l => l.stream === "stdout");
+  const olderCursor = oldestShown?.offset ? oldestShown.offset : null;
+
+  const loadOlder = async () => {
+    if (!service?.standard_out_path || olderCursor === null) return;
+    try {
+      const page = await invoke<LogPage>("read_service_logs", { logPath: service.standard_out_path, lines: 200, before: olderCursor });
+      setLogs(prev => [...page.lines.map((line, i) => ({ stream: "stdout" as const, line, offset: page.offsets[i] })), ...prev]);
+    } catch (e) { console.error(e); }
+  };
+
+  const handleSearch = async () => {
+    if (!service) return;
+    if (!searchPattern.trim()) { setSearchResult(null); return; }
+    try {
+      const result = await invoke<LogSearchResult>("search_service_logs", { label: service.label, query: { pattern: searchPattern, regex: searchRegex, before: 2, after: 2, max_matches: 200 } });
+      setSearchResult(result);
+    } catch (e) { alert(`搜索失败: ${e}`); }
   };
 
   const handleClearLogs = async () => {
@@ -735,16 +1030,57 @@ function LogPanel({ isOpen, onClose, service }: { isOpen: boolean; onClose: () =
     try { await invoke("clear_service_logs", { logPath: service.standard_out_path }); setLogs([]); } catch (e) { console.error(e); }
   };
 
+  // 实时跟随：先订阅事件再开始跟随，历史日志只读到跟随起点，避免丢行或重复；文件被截断或轮转时重新开始
   useEffect(() => {
-    if (isOpen && service) fetchLogs();
-    else setLogs([]);
-  }, [isOpen, service]);
+    setLogs([]);
+    setSearchResult(null);
+    if (!isOpen || !service) return;
+    fetchDiskUsage();
+    const label = service.label;
+    let cancelled = false;
+    let followed = false;
+    let unlisten: (() => void) | null = null;
+    // 跟随起点返回前收到的事件先缓存，之后再判断是否已包含在历史日志中
+    let positions: FollowPosition[] | null = null;
+    const pending: LogLinesEvent[] = [];
+    const isNew = (event: LogLinesEvent) => event.reset || event.end_offset > (positions?.find(p => p.stream === event.stream)?.offset ?? 0);
+    const apply = (event: LogLinesEvent) => {
+      if (event.reset) {
+        const position = positions?.find(p => p.stream === event.stream);
+        if (position) position.offset = 0;
+      }
+      // 合并模式按事件序号排列，保持 stdout 与 stderr 的读取顺序；重置只清掉对应的流
+      const incoming = event.lines.map((line, i) => ({ stream: event.stream, line, offset: event.offsets[i], seq: event.seq }));
+      setLogs(prev => [...(event.reset ? prev.filter(l => l.stream !== event.stream) : prev), ...incoming]
+        .sort((a, b) => (a.seq ?? 0) - (b.seq ?? 0))
+        .slice(-MAX_LIVE_LOG_LINES));
+    };
 
-  useEffect(() => {
-    if (!isOpen || !autoRefresh || !service) return;
-    const interval = setInterval(fetchLogs, 3000);
-    return () => clearInterval(interval);
-  }, [isOpen, autoRefresh, service]);
+    (async () => {
+      const stop = await listen<LogLinesEvent>("service-log-lines", ({ payload }) => {
+        if (payload.label !== label || (!merged && payload.stream !== "stdout")) return;
+        if (positions === null) pending.push(payload);
+        else if (isNew(payload)) apply(payload);
+      });
+      if (cancelled) { stop(); return; }
+      unlisten = stop;
+      try {
+        const result = await invoke<FollowPosition[]>("follow_service_logs", { label });
+        if (cancelled) { invoke("unfollow_service_logs", { label }).catch(e => console.error(e)); return; }
+        followed = true;
+        positions = result;
+        pending.splice(0).filter(isNew).forEach(apply);
+        const initial = await fetchLogs(result);
+        if (!cancelled) setLogs(prev => [...initial, ...prev].slice(-MAX_LIVE_LOG_LINES));
+      } catch (e) { if (!cancelled) setLogs([{ stream: "stderr", line: `Error: ${e}` }]); }
+    })();
+
+    return () => {
+      cancelled = true;
+      if (unlisten) unlisten();
+      if (followed) invoke("unfollow_service_logs", { label }).catch(e => console.error(e));
+    };
+  }, [isOpen, service, merged]);
 
   if (!isOpen) return null;
 
@@ -752,14 +1088,60 @@ function LogPanel({ isOpen, onClose, service }: { isOpen: boolean; onClose: () =
     <div style={{ position: "fixed", top: 0, right: 0, bottom: 0, width: "500px", backgroundColor: "var(--modal-bg)", boxShadow: "-4px 0 20px rgba(0,0,0,0.3)", display: "flex", flexDirection: "column", zIndex: 1000, color: "var(--text-main)" }}>
       <div style={{ padding: "16px", borderBottom: "1px solid var(--border-color)", display: "flex", alignItems: "center", justifyContent: "space-between" }}>
         <h3 style={{ margin: 0, fontSize: "14px" }}>日志: {service?.display_name || service?.label}</h3>
-        <div style={{ display: "flex", gap: "8px" }}>
+        <div style={{ display: "flex", gap: "8px", alignItems: "center" }}>
+          {diskUsage && <span title={diskUsage.files.map(f => `${f.path}: ${formatBytes(f.current_bytes)} + ${f.archives.length} 个归档 ${formatBytes(f.archived_bytes)}`).join("\n")} style={{ fontSize: "12px", color: "var(--text-secondary)" }}>占用 {formatBytes(diskUsage.total_bytes)}</span>}
+          <button onClick={handleRotate} style={{ padding: "4px 8px", borderRadius: "4px", border: "1px solid var(--border-color)", backgroundColor: "transparent", color: "var(--text-secondary)", cursor: "pointer" }}>轮转</button>
           <button onClick={handleClearLogs} style={{ padding: "4px 8px", borderRadius: "4px", border: "1px solid var(--border-color)", backgroundColor: "transparent", color: "var(--text-secondary)", cursor: "pointer" }}>清空</button>
           <button onClick={onClose} style={{ padding: "4px 8px", borderRadius: "4px", border: "none", backgroundColor: "transparent", color: "var(--text-secondary)", cursor: "pointer" }}>✕</button>
         </div>
       </div>
+      <div style={{ padding: "8px 16px", borderBottom: "1px solid var(--border-color)", display: "flex", gap: "8px", alignItems: "center", fontSize: "12px" }}>
+        <input value={searchPattern} onChange={e => setSearchPattern(e.target.value)} onKeyDown={e => { if (e.key === "Enter") handleSearch(); }} placeholder="搜索日志（含 stderr 与轮转文件）" style={{ flex: 1, padding: "4px 8px", borderRadius: "4px", border: "1px solid var(--border-color)", backgroundColor: "var(--input-bg)", color: "var(--text-main)" }} />
+        <label style={{ display: "flex", alignItems: "center", gap: "4px", color: "var(--text-secondary)" }}><input type="checkbox" checked={searchRegex} onChange={e => setSearchRegex(e.target.checked)} />正则</label>
+        <label style={{ display: "flex", alignItems: "center", gap: "4px", color: "var(--text-secondary)" }}><input type="checkbox" checked={merged} onChange={e => setMerged(e.target.checked)} />合并 stderr</label>
+        <select value={minLevel} onChange={e => setMinLevel(e.target.value as LogLevel | "")} style={{ padding: "2px 4px", borderRadius: "4px", border: "1px solid var(--border-color)", backgroundColor: "var(--input-bg)", color: "var(--text-main)", fontSize: "12px" }}>
+          <option value="">全部级别</option>
+          <option value="debug">DEBUG+</option>
+          <option value="info">INFO+</option>
+          <option value="warn">WARN+</option>
+          <option value="error">ERROR+</option>
+        </select>
+        {searchResult && <button onClick={() => { setSearchResult(null); setSearchPattern(""); }} style={{ padding: "4px 8px", borderRadius: "4px", border: "none", backgroundColor: "transparent", color: "var(--text-secondary)", cursor: "pointer" }}>✕</button>}
+      </div>
+      {records && !searchResult ? (
+        <div style={{ flex: 1, overflowY: "auto", padding: "16px", fontFamily: "monospace", fontSize: "12px", whiteSpace: "pre-wrap" }}>
+          {records.length === 0 && <div style={{ color: "var(--text-secondary)" }}>没有符合条件的日志</div>}
+          {records.map((r, i) => (
+            <div key={i} style={{ color: r.level ? LEVEL_COLORS[r.level] : undefined }}>
+              {r.segments.length > 0 ? r.segments.map((seg, j) => <span key={j} style={segmentStyle(seg)}>{seg.text}</span>) : r.text}
+            </div>
+          ))}
+        </div>
+      ) : searchResult ? (
+        <div style={{ flex: 1, overflowY: "auto", padding: "16px", fontFamily: "monospace", fontSize: "12px", whiteSpace: "pre-wrap" }}>
+          <div style={{ color: "var(--text-secondary)", marginBottom: "8px" }}>{searchResult.matches.length} 条匹配{searchResult.truncated ? "（仅显示最近的）" : ""}</div>
+          {searchResult.matches.map((m, i) => (
+            <div key={i} style={{ marginBottom: "8px", borderLeft: `2px solid ${m.stream === "stderr" ? "#ef4444" : "var(--border-color)"}`, paddingLeft: "8px" }}>
+              <div style={{ color: "var(--text-secondary)" }}>{m.file.split("/").pop()}:{m.line_number}</div>
+              {m.before.map((l, j) => <div key={`b${j}`} style={{ opacity: 0.6 }}>{l}</div>)}
+              <div style={{ fontWeight: "bold" }}>{m.line}</div>
+              {m.after.map((l, j) => <div key={`a${j}`} style={{ opacity: 0.6 }}>{l}</div>)}
+            </div>
+          ))}
+        </div>
+      ) : (
       <div style={{ flex: 1, overflowY: "auto", padding: "16px", fontFamily: "monospace", fontSize: "12px", whiteSpace: "pre-wrap" }}>
-        {logs.map((line, i) => <div key={i}>{line}</div>)}
+        {!merged && olderCursor !== null && (
+          <button onClick={loadOlder} style={{ display: "block", margin: "0 auto 8px", padding: "4px 8px", borderRadius: "4px", border: "1px solid var(--border-color)", backgroundColor: "transparent", color: "var(--text-secondary)", cursor: "pointer", fontSize: "12px" }}>加载更早的日志</button>
+        )}
+        {logs.map((l, i) => (
+          <div key={i} style={l.stream === "stderr" ? { color: "#ef4444" } : undefined}>
+            {merged && <span style={{ opacity: 0.5, marginRight: "6px" }}>{l.stream === "stderr" ? "ERR" : "OUT"}</span>}
+            {l.line}
+          </div>
+        ))}
       </div>
+      )}
     </div>
   );
 }
@@ -773,6 +1155,7 @@ function SettingsModal({ isOpen, onClose, onExport, onImport, settings, onSaveSe
   const [webdavUsername, setWebdavUsername] = useState(settings?.webdav_username || "");
   const [webdavPassword, setWebdavPassword] = useState(settings?.webdav_password || "");
   const [autoLaunch, setAutoLaunch] = useState(settings?.auto_launch || false);
+  const [notifications, setNotifications] = useState<NotificationSettings>(settings?.notifications || DEFAULT_NOTIFICATIONS);
   const [updateInfo, setUpdateInfo] = useState<UpdateInfo | null>(null);
   const [checkingUpdate, setCheckingUpdate] = useState(false);
   const [installingUpdate, setInstallingUpdate] = useState(false);
@@ -790,6 +1173,7 @@ function SettingsModal({ isOpen, onClose, onExport, onImport, settings, onSaveSe
       setWebdavUsername(settings.webdav_username || "");
       setWebdavPassword(settings.webdav_password || "");
       setAutoLaunch(settings.auto_launch || false);
+      setNotifications(settings.notifications || DEFAULT_NOTIFICATIONS);
     }
   }, [isOpen, settings]);
 
@@ -1037,6 +1421,51 @@ function SettingsModal({ isOpen, onClose, onExport, onImport, settings, onSaveSe
           </button>
         </div>
 
+        {/* 通知设置 */}
+        <div style={{ marginBottom: "24px", padding: "16px", backgroundColor: "var(--input-bg)", borderRadius: "8px", border: "1px solid var(--border-color)" }}>
+          <h3 style={{ fontSize: "13px", fontWeight: 600, color: "var(--text-secondary)", marginBottom: "12px", display: "flex", alignItems: "center", gap: "6px" }}>
+            🔔 通知
+          </h3>
+
+          <div style={{ marginBottom: "12px" }}>
+            <label style={{ display: "flex", alignItems: "center", gap: "8px", fontSize: "14px", cursor: "pointer" }}>
+              <input type="checkbox" checked={notifications.muted} onChange={(e) => setNotifications({ ...notifications, muted: e.target.checked })} style={{ width: "16px", height: "16px" }} />
+              全部静音
+            </label>
+          </div>
+
+          <div style={{ display: "flex", flexWrap: "wrap", gap: "12px", marginBottom: "12px", fontSize: "13px" }}>
+            {([["on_crash", "崩溃"], ["on_exit", "异常退出"], ["on_health_failure", "健康检查失败"], ["on_recovery", "恢复"]] as const).map(([key, text]) => (
+              <label key={key} style={{ display: "flex", alignItems: "center", gap: "6px", cursor: "pointer" }}>
+                <input type="checkbox" checked={notifications[key]} onChange={(e) => setNotifications({ ...notifications, [key]: e.target.checked })} />
+                {text}
+              </label>
+            ))}
+          </div>
+
+          <div style={{ display: "flex", gap: "12px", marginBottom: "12px" }}>
+            <div style={{ flex: 1 }}>
+              <label style={{ display: "block", fontSize: "13px", marginBottom: "6px" }}>免打扰开始</label>
+              <input type="time" value={notifications.quiet_hours?.start || ""} onChange={(e) => setNotifications({ ...notifications, quiet_hours: e.target.value ? { start: e.target.value, end: notifications.quiet_hours?.end || "08:00" } : null })} style={{ width: "100%", padding: "8px 12px", borderRadius: "6px", border: "1px solid var(--border-color)", backgroundColor: "var(--modal-bg)", color: "var(--text-main)", fontSize: "14px" }} />
+            </div>
+            <div style={{ flex: 1 }}>
+              <label style={{ display: "block", fontSize: "13px", marginBottom: "6px" }}>免打扰结束</label>
+              <input type="time" value={notifications.quiet_hours?.end || ""} disabled={!notifications.quiet_hours} onChange={(e) => notifications.quiet_hours && setNotifications({ ...notifications, quiet_hours: { ...notifications.quiet_hours, end: e.target.value } })} style={{ width: "100%", padding: "8px 12px", borderRadius: "6px", border: "1px solid var(--border-color)", backgroundColor: "var(--modal-bg)", color: "var(--text-main)", fontSize: "14px" }} />
+            </div>
+          </div>
+
+          <div style={{ display: "flex", gap: "12px" }}>
+            <div style={{ flex: 1 }}>
+              <label style={{ display: "block", fontSize: "13px", marginBottom: "6px" }}>连续失败几次后通知</label>
+              <input type="number" min={1} value={notifications.health_failure_threshold} onChange={(e) => setNotifications({ ...notifications, health_failure_threshold: Math.max(1, parseInt(e.target.value) || 1) })} style={{ width: "100%", padding: "8px 12px", borderRadius: "6px", border: "1px solid var(--border-color)", backgroundColor: "var(--modal-bg)", color: "var(--text-main)", fontSize: "14px" }} />
+            </div>
+            <div style={{ flex: 1 }}>
+              <label style={{ display: "block", fontSize: "13px", marginBottom: "6px" }}>同类通知间隔 (秒)</label>
+              <input type="number" min={0} value={notifications.debounce_secs} onChange={(e) => setNotifications({ ...notifications, debounce_secs: Math.max(0, parseInt(e.target.value) || 0) })} style={{ width: "100%", padding: "8px 12px", borderRadius: "6px", border: "1px solid var(--border-color)", backgroundColor: "var(--modal-bg)", color: "var(--text-main)", fontSize: "14px" }} />
+            </div>
+          </div>
+        </div>
+
         {/* WebDAV 设置 */}
         <div style={{ marginBottom: "24px", padding: "16px", backgroundColor: "var(--input-bg)", borderRadius: "8px", border: "1px solid var(--border-color)" }}>
           <h3 style={{ fontSize: "13px", fontWeight: 600, color: "var(--text-secondary)", marginBottom: "12px", display: "flex", alignItems: "center", gap: "6px" }}>
@@ -1106,6 +1535,7 @@ function SettingsModal({ isOpen, onClose, onExport, onImport, settings, onSaveSe
               webdav_username: webdavUsername.trim() || null,
               webdav_password: webdavPassword || null,
               auto_launch: autoLaunch,
+              notifications,
             });
             onClose();
           }} style={{ padding: "8px 16px", borderRadius: "8px", border: "none", backgroundColor: themeColor, color: "#fff", cursor: "pointer", fontSize: "13px" }}>保存</button>
@@ -1115,41 +1545,19 @@ function SettingsModal({ isOpen, onClose, onExport, onImport, settings, onSaveSe
   );
 }
 
-function ServiceCard({ service, onToggle, onRestart, onEdit, onViewLogs, settings }: { service: LaunchAgent; onToggle: () => void; onRestart: () => void; onEdit: () => void; onViewLogs: () => void; settings: AppSettings }) {
-  const { attributes, listeners, setNodeRef, transform, transition, isDragging } = useSortable({ id: service.label });
-  const [portStatus, setPortStatus] = useState<boolean | null>(null);
-  const [healthStatus, setHealthStatus] = useState<boolean | null>(null);
-
-  // Check port status
-  useEffect(() => {
-    if (!service.is_loaded || !service.port) { setPortStatus(null); return; }
-    const check = async () => {
-      try {
-        const isOpen = await invoke<boolean>("check_port", { port: service.port! });
-        setPortStatus(isOpen);
-      }
-      catch { setPortStatus(null); }
-    };
-    // 延迟首次检查，给服务时间启动端口监听
-    const initialTimer = setTimeout(check, 2000);
-    const interval = setInterval(check, 5000);
-    return () => {
-      clearTimeout(initialTimer);
-      clearInterval(interval);
-    };
-  }, [service.is_loaded, service.port]);
+function healthMark(result: HealthResult | null) {
+  if (!result) return "?";
+  return result.status === "healthy" ? "✓" : result.status === "degraded" ? "!" : "✗";
+}
 
-  // Check health status
-  useEffect(() => {
-    if (!service.is_loaded || !service.health_url) { setHealthStatus(null); return; }
-    const check = async () => {
-      try { setHealthStatus(await invoke<boolean>("check_health", { url: service.health_url! })); }
-      catch { setHealthStatus(null); }
-    };
-    check();
-    const interval = setInterval(check, 10000);
-    return () => clearInterval(interval);
-  }, [service.is_loaded, service.health_url]);
+function ServiceCard({ service, health, onToggle, onRestart, onEdit, onViewLogs, settings }: { service: LaunchAgent; health: ServiceHealth | undefined; onToggle: () => void; onRestart: () => void; onEdit: () => void; onViewLogs: () => void; settings: AppSettings }) {
+  const { attributes, listeners, setNodeRef, transform, transition, isDragging } = useSortable({ id: service.label });
+  // 状态由后端监控推送，不再在前端轮询
+  const portStatus = health?.checks.port ?? null;
+  const healthStatus = health?.checks.http ?? null;
+  const commandStatus = health?.checks.command ?? null;
+  const probeStatus = health?.checks.probe ?? null;
+  const socketStatus = health?.checks.socket ?? null;
 
   // Open service in browser
   const openServicePage = async () => {
@@ -1192,8 +1600,11 @@ function ServiceCard({ service, onToggle, onRestart, onEdit, onViewLogs, setting
         <p style={{ margin: "2px 0 0 0", fontSize: "12px", color: "var(--text-secondary)" }}>{service.description || service.label}</p>
         <div style={{ display: "flex", alignItems: "center", gap: "12px", marginTop: "4px", fontSize: "11px", color: "var(--text-muted)" }}>
           {service.pid && <span>PID: {service.pid}</span>}
-          {service.port && <span>端口: {service.port} {portStatus === true ? "✓" : portStatus === false ? "✗" : "?"}</span>}
-          {service.health_url && <span>页面: {healthStatus === true ? "✓" : healthStatus === false ? "✗" : "?"}</span>}
+          {service.port && <span title={portStatus?.message}>端口: {service.port} {healthMark(portStatus)}</span>}
+          {service.health_url && <span title={healthStatus ? `${healthStatus.message} (${healthStatus.latency_ms}ms)` : undefined}>页面: {healthMark(healthStatus)}</span>}
+          {service.command_check && <span title={commandStatus ? [commandStatus.message, commandStatus.output].filter(Boolean).join("\n") : undefined}>命令: {healthMark(commandStatus)}</span>}
+          {service.probe && <span title={probeStatus?.message}>探测: {healthMark(probeStatus)}</span>}
+          {service.socket_check && <span title={socketStatus?.message ?? service.socket_check.path}>套接字: {healthMark(socketStatus)}</span>}
         </div>
       </div>
       <div style={{ display: "flex", gap: "8px" }}>
@@ -1234,6 +1645,7 @@ function ServiceCard({ service, onToggle, onRestart, onEdit, onViewLogs, setting
 
 function App() {
   const [services, setServices] = useState<LaunchAgent[]>([]);
+  const [health, setHealth] = useState<Record<string, ServiceHealth>>({});
   const [isModalOpen, setIsModalOpen] = useState(false);
   const [editingService, setEditingService] = useState<LaunchAgent | null>(null);
   const [isLogPanelOpen, setIsLogPanelOpen] = useState(false);
@@ -1263,6 +1675,7 @@ function App() {
     webdav_username: appSettings?.webdav_username || null,
     webdav_password: appSettings?.webdav_password || null,
     auto_launch: appSettings?.auto_launch || false,
+    notifications: appSettings?.notifications || DEFAULT_NOTIFICATIONS,
   };
 
   const fetchServices = async () => {
@@ -1319,6 +1732,7 @@ function App() {
         webdav_username: s.webdav_username || null,
         webdav_password: s.webdav_password || null,
         auto_launch: s.auto_launch || false,
+        notifications: s.notifications || DEFAULT_NOTIFICATIONS,
       });
     } catch (e) { console.error(e); }
   };
@@ -1335,7 +1749,23 @@ function App() {
     }
   }, [appSettings?.theme_mode]);
 
-  useEffect(() => { fetchServices(); fetchSettings(); }, []);
+  const fetchHealth = async () => {
+    try {
+      const list = await invoke<ServiceHealth[]>("get_health_status");
+      setHealth(Object.fromEntries(list.map(h => [h.label, h])));
+    } catch (e) { console.error(e); }
+  };
+
+  useEffect(() => { fetchServices(); fetchSettings(); fetchHealth(); }, []);
+
+  // 后端监控在状态变化时推送事件
+  useEffect(() => {
+    const unlisteners = [
+      listen("service-health-changed", () => { fetchHealth(); }),
+      listen("service-state-changed", () => { fetchServices(); fetchHealth(); }),
+    ];
+    return () => { unlisteners.forEach(p => p.then(unlisten => unlisten())); };
+  }, []);
 
   const handleToggle = async (service: LaunchAgent) => {
     try {
@@ -1380,18 +1810,27 @@ function App() {
           setToast({ message: "服务已停止", type: "success" });
           fetchServices();
         } else {
-          if (service.port) {
-            const isOpen = await invoke<boolean>("check_port", { port: service.port });
-            if (isOpen) {
-              const pid = await invoke<number | null>("get_process_by_port", { port: service.port });
+          // 按需启动的服务由 ServiceMaster 占用 port，冲突检查针对实际进程监听的 backend_port
+          const servicePort = service.on_demand ? service.on_demand.backend_port : service.port;
+          if (servicePort) {
+            const portCheck = await invoke<HealthResult>("check_port", { port: servicePort, spec: service.port_check });
+            if (portCheck.status !== "unhealthy") {
+              const pid = await invoke<number | null>("get_process_by_port", { port: servicePort });
               if (pid) {
                 setPortConflict({ service, pid });
                 return;
               }
             }
           }
-          await invoke("load_service", { plistPath: service.file_path });
-          setToast({ message: "服务已启动", type: "success" });
+          const outcome = await invoke<StartOutcome>("start_service_and_wait", { label: service.label, timeoutSecs: 30 });
+          if (outcome.status === "ready") {
+            setToast({ message: `服务已启动 (${(outcome.elapsed_ms / 1000).toFixed(1)}s)`, type: "success" });
+          } else if (outcome.status === "timed_out") {
+            setToast({ message: "服务已启动，但健康检查超时", type: "error" });
+          } else {
+            const lastLine = outcome.stderr_tail[outcome.stderr_tail.length - 1];
+            setToast({ message: `服务启动后退出${outcome.exit_code != null ? ` (退出码 ${outcome.exit_code})` : ""}${lastLine ? `: ${lastLine}` : ""}`, type: "error" });
+          }
           fetchServices();
         }
       }
@@ -1517,12 +1956,12 @@ function App() {
       <div style={{ flex: 1, overflowY: "auto", padding: "16px" }}>
         <DndContext sensors={sensors} collisionDetection={closestCenter} onDragEnd={handleDragEnd} modifiers={[restrictToVerticalAxis]}>
           <SortableContext items={services.map(s => s.label)} strategy={verticalListSortingStrategy}>
-            {services.map(s => <ServiceCard key={s.label} service={s} onToggle={() => handleToggle(s)} onRestart={() => handleRestart(s)} onEdit={() => { setEditingService(s); setIsModalOpen(true); }} onViewLogs={() => { setLogService(s); setIsLogPanelOpen(true); }} settings={safeSettings} />)}
+            {services.map(s => <ServiceCard key={s.label} service={s} health={health[s.label]} onToggle={() => handleToggle(s)} onRestart={() => handleRestart(s)} onEdit={() => { setEditingService(s); setIsModalOpen(true); }} onViewLogs={() => { setLogService(s); setIsLogPanelOpen(true); }} settings={safeSettings} />)}
           </SortableContext>
         </DndContext>
       </div>
 
-      <ServiceModal isOpen={isModalOpen} onClose={() => setIsModalOpen(false)} onSave={async (c, m, f) => { if (f) await invoke("update_service", { filePath: f, config: c }); else await invoke("create_service", { config: c }); await invoke("save_service_metadata", { label: c.label, metadata: m }); fetchServices(); }} onDelete={(s) => invoke("delete_service", { filePath: s.file_path }).then(fetchServices)} editingService={editingService} />
+      <ServiceModal isOpen={isModalOpen} onClose={() => setIsModalOpen(false)} onSave={async (c, m, f) => { if (f) await invoke("update_service", { filePath: f, config: c }); else await invoke("create_service", { config: c }); const prev = await invoke<ServiceMetadata | null>("get_service_metadata", { label: c.label }); await invoke("save_service_metadata", { label: c.label, metadata: { ...prev, ...m } }); fetchServices(); }} onDelete={(s) => invoke("delete_service", { filePath: s.file_path }).then(fetchServices)} editingService={editingService} />
       <LogPanel isOpen={isLogPanelOpen} onClose={() => setIsLogPanelOpen(false)} service={logService} />
       <SettingsModal
         isOpen={isSettingsOpen}
@@ -1563,7 +2002,9 @@ function App() {
         }}
         settings={safeSettings}
         onSaveSettings={(s) => {
-          invoke("save_app_settings", { settings: s })
+          // 保留设置界面未编辑的字段（如 webhooks）
+          invoke<AppSettings>("get_app_settings")
+            .then((prev) => invoke("save_app_settings", { settings: { ...prev, ...s } }))
             .then(() => {
               setAppSettings(s);
               setToast({ message: "设置保存成功", type: "success" });